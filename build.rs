@@ -3,12 +3,14 @@ mod i18n_bundle;
 
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // Build-time embedding pipeline:
 // 1) Read assets/i18n/*.json
 // 2) Pack canonical CBOR bundle
-// 3) Emit OUT_DIR constants included by src/i18n.rs
+// 3) Hash the packed bytes so hosts can identify the translation snapshot
+// 4) Emit OUT_DIR constants included by src/i18n.rs
+// Build provenance (git sha, SOURCE_DATE_EPOCH) is exported as rustc env vars.
 fn main() {
     let i18n_dir = Path::new("assets/i18n");
     let cargo_toml = Path::new("Cargo.toml");
@@ -18,6 +20,7 @@ fn main() {
     println!("cargo:rerun-if-changed={}", component_manifest.display());
 
     sync_manifest_version(component_manifest);
+    emit_build_metadata();
 
     let locales = i18n_bundle::load_locale_files(i18n_dir)
         .unwrap_or_else(|err| panic!("failed to load locale files: {err}"));
@@ -26,12 +29,17 @@ fn main() {
 
     let out_dir = env::var("OUT_DIR").expect("OUT_DIR must be set by cargo");
     let bundle_path = Path::new(&out_dir).join("i18n.bundle.cbor");
+
+    let bundle_hash = format!("blake3:{}", blake3::hash(&bundle).to_hex());
     fs::write(&bundle_path, bundle).expect("write i18n.bundle.cbor");
 
     let rs_path = Path::new(&out_dir).join("i18n_bundle.rs");
     fs::write(
         &rs_path,
-        "pub const I18N_BUNDLE_CBOR: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/i18n.bundle.cbor\"));\n",
+        format!(
+            "pub const I18N_BUNDLE_CBOR: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/i18n.bundle.cbor\"));\n\
+             pub const I18N_BUNDLE_HASH: &str = \"{bundle_hash}\";\n"
+        ),
     )
     .expect("write i18n_bundle.rs");
 }
@@ -56,3 +64,99 @@ fn sync_manifest_version(manifest_path: &Path) {
         + "\n";
     fs::write(manifest_path, updated).expect("update component.manifest.json version");
 }
+
+// GIT_SHA overrides `git rev-parse HEAD` (tarball builds have no .git).
+// SOURCE_DATE_EPOCH is only passed through, never defaulted to "now", so
+// builds stay reproducible.
+fn emit_build_metadata() {
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let checkout = own_checkout();
+    if let Some(checkout) = &checkout {
+        watch_git_head(checkout);
+    }
+
+    let git_sha = env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.trim().is_empty())
+        .or_else(|| checkout.and_then(|_| git(&["rev-parse", "HEAD"])));
+    if let Some(sha) = git_sha {
+        println!("cargo:rustc-env=COMPONENT_BUILD_GIT_SHA={}", sha.trim());
+    }
+    if let Some(epoch) = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<u64>().ok())
+    {
+        println!("cargo:rustc-env=COMPONENT_BUILD_SOURCE_DATE_EPOCH={epoch}");
+    }
+}
+
+// Where git keeps this checkout's state. In a linked worktree or submodule
+// `.git` is a file, HEAD lives in `git_dir` and branch refs in `common_dir`.
+// `--path-format=absolute` needs git 2.31; older git reports no checkout.
+struct Checkout {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+// Only this crate's own checkout counts: when it is vendored or unpacked
+// inside another repository, that repository's HEAD says nothing about it.
+fn own_checkout() -> Option<Checkout> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").ok()?;
+    let stdout = git(&[
+        "rev-parse",
+        "--path-format=absolute",
+        "--show-toplevel",
+        "--git-dir",
+        "--git-common-dir",
+    ])?;
+    let mut lines = stdout.lines().map(str::trim);
+    let toplevel = fs::canonicalize(lines.next()?).ok()?;
+    if toplevel != fs::canonicalize(&manifest_dir).ok()? {
+        return None;
+    }
+    Some(Checkout {
+        git_dir: PathBuf::from(lines.next()?),
+        common_dir: PathBuf::from(lines.next()?),
+    })
+}
+
+// `git -C $CARGO_MANIFEST_DIR <args>`, trimmed stdout on success.
+fn git(args: &[&str]) -> Option<String> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").ok()?;
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(&manifest_dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+// Rebuild when HEAD moves: HEAD itself for checkouts, the branch ref for
+// commits. Only existing files are watched; cargo reruns the script on every
+// build for a watched path that is missing.
+fn watch_git_head(checkout: &Checkout) {
+    let head = checkout.git_dir.join("HEAD");
+    if !head.exists() {
+        return;
+    }
+    println!("cargo:rerun-if-changed={}", head.display());
+    let Ok(text) = fs::read_to_string(&head) else {
+        return;
+    };
+    if let Some(reference) = text.trim().strip_prefix("ref: ") {
+        for path in [
+            checkout.common_dir.join(reference),
+            checkout.common_dir.join("packed-refs"),
+        ] {
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Input layout: one length byte, then `current_config`, then `answers`.
+fuzz_target!(|data: &[u8]| {
+    let Some((&len, rest)) = data.split_first() else {
+        return;
+    };
+    let (current_config, answers) = rest.split_at(usize::from(len).min(rest.len()));
+    let output = component_templates::apply_answers_bytes(current_config, answers);
+    assert!(!output.is_empty());
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let output = component_templates::run_bytes(data);
+    assert!(!output.is_empty());
+});
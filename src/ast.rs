@@ -0,0 +1,797 @@
+use std::collections::BTreeSet;
+
+use greentic_types::ErrorCode;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+// Syntactic outline of Handlebars-style template text, for editors that draw
+// a dependency graph. No engine is involved: tags are classified by shape.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Node {
+    Text {
+        text: String,
+    },
+    Comment {
+        text: String,
+    },
+    Variable {
+        path: String,
+        raw: bool,
+    },
+    Helper {
+        name: String,
+        args: Vec<String>,
+        raw: bool,
+    },
+    Partial {
+        name: String,
+        args: Vec<String>,
+    },
+    // `{{#name}}`, or `{{^name}}` when `inverted`; `params` are the
+    // `as |item index|` block parameters.
+    Block {
+        name: String,
+        args: Vec<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        params: Vec<String>,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        inverted: bool,
+        children: Vec<Node>,
+    },
+    // `{{#> layout}}...{{/layout}}`: children are the fallback content.
+    PartialBlock {
+        name: String,
+        args: Vec<String>,
+        children: Vec<Node>,
+    },
+    // `{{#*inline "name"}}...{{/inline}}` and other decorator blocks; `name`
+    // is the decorator without `*`.
+    Decorator {
+        name: String,
+        args: Vec<String>,
+        children: Vec<Node>,
+    },
+    // `{{{{raw}}}}...{{{{/raw}}}}`: `text` is passed to the helper verbatim.
+    RawBlock {
+        name: String,
+        args: Vec<String>,
+        text: String,
+    },
+    // `{{else}}` or `{{^}}`; a chained `{{else if cond}}` names its helper.
+    Else {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        helper: Option<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TemplateAst {
+    pub nodes: Vec<Node>,
+    pub variables: BTreeSet<String>,
+    pub helpers: BTreeSet<String>,
+    pub partials: BTreeSet<String>,
+}
+
+// A block whose closing tag has not been seen yet.
+struct OpenBlock {
+    name: String,
+    args: Vec<String>,
+    params: Vec<String>,
+    kind: BlockKind,
+    parent: Vec<Node>,
+}
+
+impl TemplateAst {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut ast = Self::default();
+        let mut open: Vec<OpenBlock> = Vec::new();
+        let mut nodes = Vec::new();
+
+        for token in tokenize(text) {
+            let (body, raw) = match token? {
+                Token::Text(text) => {
+                    nodes.push(Node::Text {
+                        text: text.to_string(),
+                    });
+                    continue;
+                }
+                Token::Comment(text) => {
+                    nodes.push(Node::Comment {
+                        text: text.to_string(),
+                    });
+                    continue;
+                }
+                Token::Raw { body, text } => {
+                    let mut words = split_args(body).into_iter();
+                    let name = words.next().unwrap_or_default();
+                    ast.helpers.insert(name.clone());
+                    let args = ast.collect_variables(words.collect(), &open);
+                    nodes.push(Node::RawBlock {
+                        name,
+                        args,
+                        text: text.to_string(),
+                    });
+                    continue;
+                }
+                Token::Tag { body, raw } => (body, raw),
+            };
+            match classify(body) {
+                Tag::Partial(expr) => {
+                    let mut words = split_args(expr).into_iter();
+                    let name = words.next().unwrap_or_default();
+                    if subexpression(&name).is_some() {
+                        ast.reference(&name, &open);
+                    } else {
+                        ast.partials.insert(name.clone());
+                    }
+                    let args = ast.collect_variables(words.collect(), &open);
+                    nodes.push(Node::Partial { name, args });
+                }
+                Tag::Open { expr, kind } => {
+                    let (words, params) = split_block_params(split_args(expr));
+                    let mut words = words.into_iter();
+                    let name = words.next().unwrap_or_default();
+                    let args: Vec<String> = words.collect();
+                    let name = match kind {
+                        BlockKind::Helper => {
+                            ast.helpers.insert(name.clone());
+                            name
+                        }
+                        // `{{^items}}` is an inverted section over a value.
+                        BlockKind::Inverted if args.is_empty() => {
+                            ast.reference(&name, &open);
+                            name
+                        }
+                        BlockKind::Inverted => {
+                            ast.helpers.insert(name.clone());
+                            name
+                        }
+                        BlockKind::Partial => {
+                            ast.partials.insert(name.clone());
+                            name
+                        }
+                        BlockKind::Decorator => name.trim_start_matches('*').to_string(),
+                    };
+                    let args = ast.collect_variables(args, &open);
+                    open.push(OpenBlock {
+                        name,
+                        args,
+                        params,
+                        kind,
+                        parent: std::mem::take(&mut nodes),
+                    });
+                }
+                Tag::Close(closing) => {
+                    let Some(block) = open.pop() else {
+                        return Err(format!("`{{{{/{closing}}}}}` closes no block"));
+                    };
+                    if block.name != closing {
+                        return Err(format!(
+                            "`{{{{#{}}}}}` is closed by `{{{{/{closing}}}}}`",
+                            block.name
+                        ));
+                    }
+                    let children = std::mem::replace(&mut nodes, block.parent);
+                    let (name, args) = (block.name, block.args);
+                    nodes.push(match block.kind {
+                        BlockKind::Helper | BlockKind::Inverted => Node::Block {
+                            name,
+                            args,
+                            params: block.params,
+                            inverted: block.kind == BlockKind::Inverted,
+                            children,
+                        },
+                        BlockKind::Partial => Node::PartialBlock {
+                            name,
+                            args,
+                            children,
+                        },
+                        BlockKind::Decorator => Node::Decorator {
+                            name,
+                            args,
+                            children,
+                        },
+                    });
+                }
+                Tag::Else(expr) => {
+                    let mut words = split_args(expr).into_iter();
+                    let helper = words.next();
+                    if let Some(helper) = &helper {
+                        ast.helpers.insert(helper.clone());
+                    }
+                    let args = ast.collect_variables(words.collect(), &open);
+                    nodes.push(Node::Else { helper, args });
+                }
+                Tag::Expression(expr) => {
+                    let mut words = split_args(expr).into_iter();
+                    let name = words.next().unwrap_or_default();
+                    let args: Vec<String> = words.collect();
+                    if args.is_empty() {
+                        ast.reference(&name, &open);
+                        nodes.push(Node::Variable { path: name, raw });
+                    } else {
+                        ast.helpers.insert(name.clone());
+                        let args = ast.collect_variables(args, &open);
+                        nodes.push(Node::Helper { name, args, raw });
+                    }
+                }
+            }
+        }
+        if let Some(block) = open.last() {
+            return Err(format!("`{{{{#{}}}}}` is never closed", block.name));
+        }
+        ast.nodes = nodes;
+        Ok(ast)
+    }
+
+    fn collect_variables(&mut self, args: Vec<String>, open: &[OpenBlock]) -> Vec<String> {
+        for arg in &args {
+            self.reference(arg, open);
+        }
+        args
+    }
+
+    // Records what one argument refers to: the value side of `key=value`,
+    // the helper and arguments of a subexpression, or a path. Literals and
+    // block parameters in scope are not variables.
+    fn reference(&mut self, arg: &str, open: &[OpenBlock]) {
+        let value = hash_value(arg);
+        if let Some(inner) = subexpression(value) {
+            let mut words = split_args(inner).into_iter();
+            if let Some(helper) = words.next() {
+                self.helpers.insert(helper);
+            }
+            for word in words {
+                self.reference(&word, open);
+            }
+        } else if is_path(value) && !is_block_param(value, open) {
+            self.variables.insert(value.to_string());
+        }
+    }
+}
+
+// One piece of template text as produced by `tokenize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    Comment(&'a str),
+    // Tag body without braces, `~` or `&`; `raw` for `{{{ }}}` and `{{& }}`.
+    Tag { body: &'a str, raw: bool },
+    // `{{{{body}}}}text{{{{/name}}}}`; `text` is not tokenized.
+    Raw { body: &'a str, text: &'a str },
+}
+
+// Splits template text into text, comments and tags. `}}` inside a quoted
+// literal, a `[...]` segment or a `{{!-- --}}` comment does not end a tag.
+// Yields one error for an unterminated tag and then stops.
+pub(crate) fn tokenize(text: &str) -> impl Iterator<Item = Result<Token<'_>, String>> {
+    let mut pos = 0;
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed || pos >= text.len() {
+            return None;
+        }
+        let rest = &text[pos..];
+        let start = rest.find("{{").unwrap_or(rest.len());
+        if start > 0 {
+            pos += start;
+            return Some(Ok(Token::Text(&rest[..start])));
+        }
+        match scan_tag(rest) {
+            Some((token, len)) => {
+                pos += len;
+                Some(Ok(token))
+            }
+            None => {
+                failed = true;
+                Some(Err(format!("unclosed tag at byte {pos}")))
+            }
+        }
+    })
+}
+
+// `rest` starts with `{{`; returns the tag and its length in bytes.
+fn scan_tag(rest: &str) -> Option<(Token<'_>, usize)> {
+    if let Some(inner) = rest.strip_prefix("{{{{") {
+        return scan_raw_block(inner).map(|(token, len)| (token, 4 + len));
+    }
+    let triple = rest.starts_with("{{{");
+    let open = if triple { 3 } else { 2 };
+    let inner = &rest[open..];
+    let body_start = usize::from(inner.starts_with('~'));
+
+    if !triple && inner[body_start..].starts_with("!--") {
+        let comment_start = body_start + 3;
+        let (end, close_len) = find_long_comment_end(&inner[comment_start..])?;
+        let text = inner[comment_start..comment_start + end].trim();
+        return Some((Token::Comment(text), open + comment_start + end + close_len));
+    }
+    if !triple && inner[body_start..].starts_with('!') {
+        let end = inner.find("}}")?;
+        let text = inner[body_start + 1..end].trim_end_matches('~').trim();
+        return Some((Token::Comment(text), open + end + 2));
+    }
+
+    let close = if triple { "}}}" } else { "}}" };
+    let end = find_close(inner, close)?;
+    let body = inner[..end].trim_matches('~').trim();
+    let (body, raw) = match body.strip_prefix('&') {
+        Some(body) => (body.trim(), true),
+        None => (body, triple),
+    };
+    Some((Token::Tag { body, raw }, open + end + close.len()))
+}
+
+// `inner` follows `{{{{`. A stray `{{{{/name}}}}` comes back as a closing
+// tag so the parser reports it.
+fn scan_raw_block(inner: &str) -> Option<(Token<'_>, usize)> {
+    let end = find_close(inner, "}}}}")?;
+    let body = inner[..end].trim();
+    let content_start = end + 4;
+    if body.starts_with('/') {
+        return Some((Token::Tag { body, raw: false }, content_start));
+    }
+    let name = body.split_whitespace().next().unwrap_or_default();
+    let closing = format!("{{{{{{{{/{name}}}}}}}}}");
+    let text_len = inner[content_start..].find(&closing)?;
+    let text = &inner[content_start..content_start + text_len];
+    Some((
+        Token::Raw { body, text },
+        content_start + text_len + closing.len(),
+    ))
+}
+
+// Offset and length of the `--}}` / `--~}}` that ends a long comment.
+fn find_long_comment_end(body: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    loop {
+        let dashes = from + body[from..].find("--")?;
+        let after = &body[dashes + 2..];
+        if after.starts_with("}}") {
+            return Some((dashes, 4));
+        }
+        if after.starts_with("~}}") {
+            return Some((dashes, 5));
+        }
+        from = dashes + 1;
+    }
+}
+
+fn find_close(inner: &str, close: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, ch) in inner.char_indices() {
+        match quote {
+            Some(end) if ch == end => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '[' => quote = Some(']'),
+            None if inner[index..].starts_with(close) => return Some(index),
+            None => {}
+        }
+    }
+    None
+}
+
+// Shape of a tag body, shared by the parser and `TemplateStats::scan`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tag<'a> {
+    Partial(&'a str),
+    Open { expr: &'a str, kind: BlockKind },
+    Close(&'a str),
+    // `else`/`^` with the chained helper expression, if any.
+    Else(&'a str),
+    Expression(&'a str),
+}
+
+// `{{#name}}`, `{{^name}}`, `{{#> partial}}` or `{{#*decorator}}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlockKind {
+    Helper,
+    Inverted,
+    Partial,
+    Decorator,
+}
+
+pub(crate) fn classify(body: &str) -> Tag<'_> {
+    if let Some(expr) = body.strip_prefix('>') {
+        Tag::Partial(expr.trim())
+    } else if let Some(expr) = body.strip_prefix('#') {
+        let (expr, kind) = match expr.strip_prefix('>') {
+            Some(partial) => (partial, BlockKind::Partial),
+            None if expr.starts_with('*') => (expr, BlockKind::Decorator),
+            None => (expr, BlockKind::Helper),
+        };
+        Tag::Open {
+            expr: expr.trim(),
+            kind,
+        }
+    } else if let Some(expr) = body.strip_prefix('^') {
+        match expr.trim() {
+            "" => Tag::Else(""),
+            expr => Tag::Open {
+                expr,
+                kind: BlockKind::Inverted,
+            },
+        }
+    } else if let Some(name) = body.strip_prefix('/') {
+        Tag::Close(name.trim())
+    } else if body == "else" {
+        Tag::Else("")
+    } else if let Some(expr) = body
+        .strip_prefix("else")
+        .filter(|expr| expr.starts_with(char::is_whitespace))
+    {
+        Tag::Else(expr.trim())
+    } else {
+        Tag::Expression(body)
+    }
+}
+
+impl Tag<'_> {
+    // Helper invocations the tag makes: block helpers, helper calls with
+    // arguments, chained `else` helpers, and every subexpression. Partials
+    // and decorators are not helpers.
+    pub(crate) fn helper_calls(&self) -> usize {
+        let (expr, own) = match *self {
+            Tag::Open {
+                expr,
+                kind: BlockKind::Helper,
+            } => (expr, true),
+            Tag::Else(expr) => (expr, !expr.is_empty()),
+            Tag::Open {
+                expr,
+                kind: BlockKind::Inverted,
+            }
+            | Tag::Expression(expr) => (expr, split_args(expr).len() > 1),
+            Tag::Open {
+                expr,
+                kind: BlockKind::Partial | BlockKind::Decorator,
+            }
+            | Tag::Partial(expr) => (expr, false),
+            Tag::Close(_) => return 0,
+        };
+        let (words, _) = split_block_params(split_args(expr));
+        usize::from(own)
+            + words
+                .iter()
+                .map(|word| subexpression_calls(word))
+                .sum::<usize>()
+    }
+}
+
+fn subexpression_calls(word: &str) -> usize {
+    subexpression(hash_value(word)).map_or(0, |inner| {
+        1 + split_args(inner)
+            .iter()
+            .skip(1)
+            .map(|word| subexpression_calls(word))
+            .sum::<usize>()
+    })
+}
+
+// Whitespace split that keeps quoted strings, `[...]` segments,
+// subexpressions and `|block params|` whole.
+fn split_args(expr: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut depth = 0usize;
+    let mut in_params = false;
+    for ch in expr.chars() {
+        match quote {
+            Some(end) if ch == end => quote = None,
+            Some(_) => {}
+            None => match ch {
+                '"' | '\'' => quote = Some(ch),
+                '[' => quote = Some(']'),
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                '|' if depth == 0 => in_params = !in_params,
+                _ if ch.is_whitespace() && depth == 0 && !in_params => {
+                    if !current.is_empty() {
+                        args.push(std::mem::take(&mut current));
+                    }
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+// Separates a trailing `as |item index|` from the block's arguments.
+fn split_block_params(mut words: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let [.., keyword, params] = words.as_slice() else {
+        return (words, Vec::new());
+    };
+    let Some(params) = params
+        .strip_prefix('|')
+        .and_then(|params| params.strip_suffix('|'))
+        .filter(|_| keyword == "as")
+    else {
+        return (words, Vec::new());
+    };
+    let params = params.split_whitespace().map(str::to_string).collect();
+    words.truncate(words.len() - 2);
+    (words, params)
+}
+
+// `value` of a `key=value` hash argument, or the argument itself.
+fn hash_value(arg: &str) -> &str {
+    match arg.split_once('=') {
+        Some((key, value))
+            if !key.is_empty()
+                && key
+                    .chars()
+                    .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '-') =>
+        {
+            value
+        }
+        _ => arg,
+    }
+}
+
+fn subexpression(word: &str) -> Option<&str> {
+    word.strip_prefix('(')?.strip_suffix(')')
+}
+
+fn is_path(word: &str) -> bool {
+    !word.is_empty()
+        && !word.starts_with(['"', '\'', '('])
+        && word.parse::<f64>().is_err()
+        && !matches!(word, "true" | "false" | "null" | "undefined")
+}
+
+// `item` and `item.name` inside `{{#each list as |item|}}` name the block
+// parameter, not context data.
+fn is_block_param(path: &str, open: &[OpenBlock]) -> bool {
+    let root = path.split(['.', '/']).next().unwrap_or(path);
+    open.iter()
+        .any(|block| block.params.iter().any(|param| param == root))
+}
+
+// `run` form: `{ "$operation": "ast", "text": "..." }`.
+pub(crate) fn ast_output(input: &JsonValue) -> JsonValue {
+    let Some(text) = input.get("text").and_then(|v| v.as_str()) else {
+        return crate::codec::error_document(
+            ErrorCode::InvalidInput,
+            "`text` template is required",
+        );
+    };
+    match TemplateAst::parse(text) {
+        Ok(ast) => serde_json::to_value(ast)
+            .unwrap_or_else(|err| crate::codec::internal_error(err.to_string())),
+        Err(message) => crate::codec::error_document(ErrorCode::InvalidInput, message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nests_blocks_and_collects_references() {
+        let ast = TemplateAst::parse(
+            "Hi {{name}}{{!-- note --}}{{#each payload.items}}{{upper this.label}}{{else}}none{{/each}}{{> footer user=payload.user}}{{{payload}}}",
+        )
+        .expect("parse");
+
+        assert_eq!(
+            ast.variables,
+            BTreeSet::from(
+                [
+                    "name",
+                    "payload",
+                    "payload.items",
+                    "payload.user",
+                    "this.label"
+                ]
+                .map(String::from)
+            )
+        );
+        assert_eq!(
+            ast.helpers,
+            BTreeSet::from(["each", "upper"].map(String::from))
+        );
+        assert_eq!(ast.partials, BTreeSet::from(["footer".to_string()]));
+        assert_eq!(
+            ast.nodes[3],
+            Node::Block {
+                name: "each".to_string(),
+                args: vec!["payload.items".to_string()],
+                params: Vec::new(),
+                inverted: false,
+                children: vec![
+                    Node::Helper {
+                        name: "upper".to_string(),
+                        args: vec!["this.label".to_string()],
+                        raw: false,
+                    },
+                    Node::Else {
+                        helper: None,
+                        args: Vec::new(),
+                    },
+                    Node::Text {
+                        text: "none".to_string(),
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            ast.nodes.last(),
+            Some(&Node::Variable {
+                path: "payload".to_string(),
+                raw: true,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_collects_subexpression_helpers_and_arguments() {
+        let ast = TemplateAst::parse(r#"{{#if (eq (lower user.role) "admin")}}{{format date style=(pick opts.style)}}{{/if}}"#)
+            .expect("parse");
+        assert_eq!(
+            ast.helpers,
+            BTreeSet::from(["eq", "format", "if", "lower", "pick"].map(String::from))
+        );
+        assert_eq!(
+            ast.variables,
+            BTreeSet::from(["date", "opts.style", "user.role"].map(String::from))
+        );
+        let Node::Block { args, .. } = &ast.nodes[0] else {
+            panic!("block expected: {:?}", ast.nodes[0]);
+        };
+        assert_eq!(args, &[r#"(eq (lower user.role) "admin")"#.to_string()]);
+    }
+
+    #[test]
+    fn parse_keeps_else_if_chains_in_one_block() {
+        let ast = TemplateAst::parse("{{#if a}}1{{else if b}}2{{else}}3{{/if}}").expect("parse");
+        let Node::Block { children, .. } = &ast.nodes[0] else {
+            panic!("block expected: {:?}", ast.nodes[0]);
+        };
+        assert_eq!(
+            children[1],
+            Node::Else {
+                helper: Some("if".to_string()),
+                args: vec!["b".to_string()],
+            }
+        );
+        assert!(ast.variables.contains("b"));
+    }
+
+    #[test]
+    fn parse_records_block_params_and_scopes_them() {
+        let ast = TemplateAst::parse(
+            "{{#each users as |user index|}}{{user.name}}{{index}}{{title}}{{/each}}",
+        )
+        .expect("parse");
+        let Node::Block { args, params, .. } = &ast.nodes[0] else {
+            panic!("block expected: {:?}", ast.nodes[0]);
+        };
+        assert_eq!(args, &["users".to_string()]);
+        assert_eq!(params, &["user".to_string(), "index".to_string()]);
+        assert_eq!(
+            ast.variables,
+            BTreeSet::from(["title", "users"].map(String::from))
+        );
+    }
+
+    #[test]
+    fn parse_treats_caret_as_else_or_inverted_block() {
+        let ast =
+            TemplateAst::parse("{{#if a}}x{{^}}y{{/if}}{{^items}}none{{/items}}").expect("parse");
+        let Node::Block { children, .. } = &ast.nodes[0] else {
+            panic!("block expected: {:?}", ast.nodes[0]);
+        };
+        assert_eq!(
+            children[1],
+            Node::Else {
+                helper: None,
+                args: Vec::new(),
+            }
+        );
+        assert!(matches!(
+            &ast.nodes[1],
+            Node::Block { name, inverted: true, .. } if name == "items"
+        ));
+        assert!(ast.variables.contains("items"));
+        assert!(!ast.helpers.contains("items"));
+    }
+
+    #[test]
+    fn parse_skips_closing_braces_in_comments_and_literals() {
+        let ast = TemplateAst::parse(
+            r#"{{!-- {{#if a}} }} --}}{{default name "}}"}}{{{wrap '}}}' }}}{{~! short ~}}"#,
+        )
+        .expect("parse");
+        assert_eq!(
+            ast.nodes,
+            vec![
+                Node::Comment {
+                    text: "{{#if a}} }}".to_string(),
+                },
+                Node::Helper {
+                    name: "default".to_string(),
+                    args: vec!["name".to_string(), r#""}}""#.to_string()],
+                    raw: false,
+                },
+                Node::Helper {
+                    name: "wrap".to_string(),
+                    args: vec!["'}}}'".to_string()],
+                    raw: true,
+                },
+                Node::Comment {
+                    text: "short".to_string(),
+                },
+            ]
+        );
+        assert!(TemplateAst::parse("{{!-- never closed }}").is_err());
+        assert!(TemplateAst::parse(r#"{{upper "}}"#).is_err());
+    }
+
+    #[test]
+    fn parse_supports_partial_blocks_inline_partials_and_raw_blocks() {
+        let ast = TemplateAst::parse(concat!(
+            r#"{{#*inline "row"}}{{cell}}{{/inline}}"#,
+            "{{#> layout title=page.title}}{{> row}}{{/layout}}",
+            "{{{{raw}}}}{{#if}} {{/each}}{{{{/raw}}}}",
+        ))
+        .expect("parse");
+        assert_eq!(
+            ast.nodes,
+            vec![
+                Node::Decorator {
+                    name: "inline".to_string(),
+                    args: vec![r#""row""#.to_string()],
+                    children: vec![Node::Variable {
+                        path: "cell".to_string(),
+                        raw: false,
+                    }],
+                },
+                Node::PartialBlock {
+                    name: "layout".to_string(),
+                    args: vec!["title=page.title".to_string()],
+                    children: vec![Node::Partial {
+                        name: "row".to_string(),
+                        args: Vec::new(),
+                    }],
+                },
+                Node::RawBlock {
+                    name: "raw".to_string(),
+                    args: Vec::new(),
+                    text: "{{#if}} {{/each}}".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            ast.partials,
+            BTreeSet::from(["layout", "row"].map(String::from))
+        );
+        assert_eq!(ast.helpers, BTreeSet::from(["raw".to_string()]));
+        assert!(ast.variables.contains("page.title"));
+
+        assert!(TemplateAst::parse("{{#> layout}}x").is_err());
+        assert!(TemplateAst::parse("{{{{raw}}}}x").is_err());
+        assert!(TemplateAst::parse("{{{{/raw}}}}").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_unbalanced_blocks() {
+        assert!(TemplateAst::parse("{{#if a}}x").is_err());
+        assert!(TemplateAst::parse("{{#if a}}x{{/each}}").is_err());
+        assert!(TemplateAst::parse("{{/if}}").is_err());
+        assert!(TemplateAst::parse("{{name").is_err());
+
+        let output = ast_output(&serde_json::json!({ "text": "{{#if a}}" }));
+        assert_eq!(output["error"]["code"], "invalid_input");
+    }
+}
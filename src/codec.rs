@@ -0,0 +1,170 @@
+use greentic_types::ErrorCode;
+use greentic_types::cbor::canonical;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+// Wire encodings accepted by the byte-level entrypoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireEncoding {
+    Cbor,
+    Json,
+    #[serde(rename = "msgpack")]
+    MessagePack,
+}
+
+impl WireEncoding {
+    pub const ALL: [Self; 3] = [Self::Cbor, Self::Json, Self::MessagePack];
+
+    // The leading byte of the top-level map selects the encoding:
+    // - JSON documents start with `{` or `[` (after whitespace). As CBOR those
+    //   bytes open a text string (0x7b, major type 3) / byte string (0x5b,
+    //   major type 2) with an 8-byte length, never a map.
+    // - MessagePack maps start with fixmap 0x80..=0x8f or map16/map32
+    //   0xde/0xdf; CBOR maps use 0xa0..=0xbf, so the two never collide.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            Some(b'{') | Some(b'[') => Self::Json,
+            Some(0x80..=0x8f) | Some(0xde) | Some(0xdf) => Self::MessagePack,
+            _ => Self::Cbor,
+        }
+    }
+}
+
+// Decodes `bytes` in the detected encoding, falling back to CBOR/JSON before
+// giving up with an empty object (the historical `run` behaviour). MessagePack
+// is never a fallback: almost any byte string parses as some msgpack scalar.
+// The returned encoding is the one that decoded, so replies match the caller.
+pub fn decode(bytes: &[u8]) -> (WireEncoding, JsonValue) {
+    try_decode(bytes).unwrap_or_else(|| (WireEncoding::detect(bytes), serde_json::json!({})))
+}
+
+// `decode` without the empty-object fallback, for callers that report
+// undecodable input instead of silently carrying on.
+pub fn try_decode(bytes: &[u8]) -> Option<(WireEncoding, JsonValue)> {
+    let detected = WireEncoding::detect(bytes);
+    std::iter::once(detected)
+        .chain(
+            [WireEncoding::Cbor, WireEncoding::Json]
+                .into_iter()
+                .filter(|other| *other != detected),
+        )
+        .find_map(|encoding| decode_as(encoding, bytes).map(|value| (encoding, value)))
+}
+
+fn decode_as(encoding: WireEncoding, bytes: &[u8]) -> Option<JsonValue> {
+    match encoding {
+        WireEncoding::Cbor => canonical::from_cbor(bytes).ok(),
+        WireEncoding::Json => serde_json::from_slice(bytes).ok(),
+        WireEncoding::MessagePack => rmp_serde::from_slice(bytes).ok(),
+    }
+}
+
+// Canonical CBOR, compact JSON, or MessagePack with named map keys.
+pub fn encode<T: Serialize>(encoding: WireEncoding, value: &T) -> Result<Vec<u8>, String> {
+    match encoding {
+        WireEncoding::Cbor => {
+            canonical::to_canonical_cbor_allow_floats(value).map_err(|err| err.to_string())
+        }
+        WireEncoding::Json => serde_json::to_vec(value).map_err(|err| err.to_string()),
+        WireEncoding::MessagePack => rmp_serde::to_vec_named(value).map_err(|err| err.to_string()),
+    }
+}
+
+// Like `encode`, but never fails: a serialization error is reported as an
+// `internal` error document in the same encoding instead of trapping the
+// instance. The empty object is a last resort if even that cannot encode.
+pub fn encode_or_error<T: Serialize>(encoding: WireEncoding, value: &T) -> Vec<u8> {
+    encode(encoding, value)
+        .or_else(|message| encode(encoding, &internal_error(message)))
+        .unwrap_or_else(|_| empty_object(encoding))
+}
+
+// `{ "error": { "code": "<error_code>", "message": ... } }`
+pub fn error_document(code: ErrorCode, message: impl Into<String>) -> JsonValue {
+    serde_json::json!({
+        "error": { "code": code, "message": message.into() }
+    })
+}
+
+pub fn internal_error(message: impl Into<String>) -> JsonValue {
+    error_document(ErrorCode::Internal, message)
+}
+
+fn empty_object(encoding: WireEncoding) -> Vec<u8> {
+    match encoding {
+        WireEncoding::Cbor => vec![0xa0],
+        WireEncoding::Json => b"{}".to_vec(),
+        WireEncoding::MessagePack => vec![0x80],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_json_documents_and_defaults_to_cbor() {
+        assert_eq!(WireEncoding::detect(b" {\"input\":1}"), WireEncoding::Json);
+        assert_eq!(WireEncoding::detect(b"[1]"), WireEncoding::Json);
+        let cbor = encode(WireEncoding::Cbor, &serde_json::json!({ "input": "x" })).unwrap();
+        assert_eq!(WireEncoding::detect(&cbor), WireEncoding::Cbor);
+        let msgpack = encode(WireEncoding::MessagePack, &serde_json::json!({ "a": 1 })).unwrap();
+        assert_eq!(WireEncoding::detect(&msgpack), WireEncoding::MessagePack);
+        assert_eq!(WireEncoding::detect(b""), WireEncoding::Cbor);
+    }
+
+    #[test]
+    fn decode_round_trips_every_encoding() {
+        let value = serde_json::json!({ "input": "hi", "n": [1, 2] });
+        for encoding in WireEncoding::ALL {
+            let bytes = encode(encoding, &value).unwrap();
+            assert_eq!(decode(&bytes), (encoding, value.clone()));
+        }
+    }
+
+    #[test]
+    fn cbor_byte_string_starting_with_bracket_is_not_json() {
+        // 0x5b: byte string with an 8-byte length (3), then the bytes. It is
+        // neither JSON nor a document-shaped CBOR value.
+        let bytes = [0x5b, 0, 0, 0, 0, 0, 0, 0, 3, b'a', b'b', b'c'];
+        assert_eq!(WireEncoding::detect(&bytes), WireEncoding::Json);
+        assert_eq!(try_decode(&bytes), None);
+    }
+
+    #[test]
+    fn decode_reports_the_encoding_that_decoded() {
+        // 0x82 is a msgpack fixmap header but also a CBOR two-item array.
+        let cbor = encode(WireEncoding::Cbor, &serde_json::json!([1, 2])).unwrap();
+        assert_eq!(WireEncoding::detect(&cbor), WireEncoding::MessagePack);
+        assert_eq!(
+            decode(&cbor),
+            (WireEncoding::Cbor, serde_json::json!([1, 2]))
+        );
+    }
+
+    #[test]
+    fn decode_falls_back_to_empty_object() {
+        assert_eq!(decode(b"{oops").1, serde_json::json!({}));
+        assert_eq!(decode(&[0xff, 0x00]).1, serde_json::json!({}));
+        assert_eq!(decode(&[0xde, 0x00]).1, serde_json::json!({}));
+    }
+
+    #[test]
+    fn encode_or_error_reports_unserializable_values_as_internal() {
+        struct Broken;
+        impl Serialize for Broken {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("broken"))
+            }
+        }
+
+        for encoding in WireEncoding::ALL {
+            let (decoded_as, value) = decode(&encode_or_error(encoding, &Broken));
+            assert_eq!(decoded_as, encoding);
+            assert_eq!(value["error"]["code"], "internal");
+            let message = value["error"]["message"].as_str().expect("message");
+            assert!(message.contains("broken"), "{message}");
+        }
+    }
+}
@@ -0,0 +1,497 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::ast::TemplateAst;
+use crate::limits::{LimitViolation, TemplateLimits};
+
+// Defaults documented in schemas/component.schema.json. `handle_message`
+// does not read these fields yet, so describe does not advertise them.
+pub const DEFAULT_OUTPUT_PATH: &str = "text";
+pub const DEFAULT_WRAP: bool = true;
+pub const DEFAULT_ROUTING: &str = "out";
+
+// Typed view of the `templates` config object documented in
+// schemas/component.schema.json, for host-side tools embedding this crate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplatesConfig {
+    pub text: String,
+    #[serde(default = "default_output_path")]
+    pub output_path: String,
+    #[serde(default = "default_wrap")]
+    pub wrap: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<TemplateVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<TemplateLimits>,
+}
+
+// One saved edit of `templates.text`; `active` names the live entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateVersion {
+    pub id: String,
+    pub text: String,
+    // Unix seconds from the `created_at` answer; absent when the host sent
+    // none and for the text seeded from a pre-history config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+// Oldest versions beyond this are dropped (the active one is always kept).
+pub const MAX_TEMPLATE_VERSIONS: usize = 20;
+
+impl TemplatesConfig {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            output_path: default_output_path(),
+            wrap: default_wrap(),
+            routing: None,
+            versions: Vec::new(),
+            active: None,
+            limits: None,
+        }
+    }
+
+    pub fn builder(text: impl Into<String>) -> TemplatesConfigBuilder {
+        TemplatesConfigBuilder {
+            config: Self::new(text),
+        }
+    }
+
+    // Parses a component config document (`{ "templates": { ... } }`).
+    // Legacy `{ "component": ..., "config": { ... } }` wrappers are unwrapped.
+    pub fn from_config(config: &JsonValue) -> Result<Self, serde_json::Error> {
+        let (config, _) = normalized_config(config);
+        let templates = config.get("templates").cloned().unwrap_or(JsonValue::Null);
+        serde_json::from_value(templates)
+    }
+
+    // Component config document accepted by `from_config` and the schema.
+    pub fn to_config(&self) -> JsonValue {
+        serde_json::json!({ "templates": self })
+    }
+
+    // Routing target, falling back to the documented default.
+    pub fn routing_or_default(&self) -> &str {
+        self.routing.as_deref().unwrap_or(DEFAULT_ROUTING)
+    }
+}
+
+// Programmatic construction for host-side flow generators. `build` applies
+// the checks `apply_answers` would, so a bad config fails here instead of
+// at deploy time.
+#[derive(Debug, Clone)]
+pub struct TemplatesConfigBuilder {
+    config: TemplatesConfig,
+}
+
+impl TemplatesConfigBuilder {
+    pub fn output_path(mut self, output_path: impl Into<String>) -> Self {
+        self.config.output_path = output_path.into();
+        self
+    }
+
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.config.wrap = wrap;
+        self
+    }
+
+    pub fn routing(mut self, routing: impl Into<String>) -> Self {
+        self.config.routing = Some(routing.into());
+        self
+    }
+
+    pub fn limits(mut self, limits: TemplateLimits) -> Self {
+        self.config.limits = Some(limits);
+        self
+    }
+
+    pub fn build(self) -> Result<TemplatesConfig, ConfigError> {
+        let config = self.config;
+        if config.output_path.trim().is_empty() {
+            return Err(ConfigError::EmptyOutputPath);
+        }
+        if config
+            .routing
+            .as_deref()
+            .is_some_and(|routing| routing.trim().is_empty())
+        {
+            return Err(ConfigError::EmptyRouting);
+        }
+        TemplateLimits::from_config(&config.to_config()).check(&config.text)?;
+        TemplateAst::parse(&config.text)
+            .map_err(|message| ConfigError::InvalidTemplate { message })?;
+        Ok(config)
+    }
+}
+
+// Why `TemplatesConfigBuilder::build` refused a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    EmptyOutputPath,
+    EmptyRouting,
+    Limit(LimitViolation),
+    InvalidTemplate { message: String },
+}
+
+impl From<LimitViolation> for ConfigError {
+    fn from(violation: LimitViolation) -> Self {
+        Self::Limit(violation)
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyOutputPath => f.write_str("output_path must not be empty"),
+            Self::EmptyRouting => f.write_str("routing must not be empty when set"),
+            Self::Limit(violation) => violation.fmt(f),
+            Self::InvalidTemplate { message } => write!(f, "text: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Limit(violation) => Some(violation),
+            _ => None,
+        }
+    }
+}
+
+// Hand-written flow YAML often says `Templates:` or `template:`. Renames any
+// case variant of `templates`/`template` at the config root to `templates`
+// and returns a deprecation warning; an alias next to a real `templates`
+// key is dropped, since the canonical key wins.
+pub fn normalize_templates_key(config: &mut Map<String, JsonValue>) -> Option<String> {
+    let aliases: Vec<String> = config
+        .keys()
+        .filter(|key| {
+            *key != "templates"
+                && (key.eq_ignore_ascii_case("templates") || key.eq_ignore_ascii_case("template"))
+        })
+        .cloned()
+        .collect();
+    if aliases.is_empty() {
+        return None;
+    }
+
+    let mut renamed = None;
+    for alias in &aliases {
+        let value = config.remove(alias);
+        if let Some(value) = value
+            && !config.contains_key("templates")
+        {
+            config.insert("templates".to_string(), value);
+            renamed = Some(alias.clone());
+        }
+    }
+    let ignored: Vec<_> = aliases
+        .iter()
+        .filter(|alias| Some(*alias) != renamed.as_ref())
+        .map(|alias| format!("`{alias}`"))
+        .collect();
+    let mut warning = match &renamed {
+        Some(alias) => format!("config key `{alias}` is deprecated; use `templates`"),
+        None => "config key `templates` takes precedence".to_string(),
+    };
+    if !ignored.is_empty() {
+        warning.push_str(&format!("; ignored {}", ignored.join(", ")));
+    }
+    Some(warning)
+}
+
+// The component config as every check should see it: the legacy
+// `{ "config": { ... } }` wrapper unwrapped and the `templates` key
+// normalized. Returns the deprecation warning, if any.
+pub fn normalized_config(config: &JsonValue) -> (JsonValue, Option<String>) {
+    let config = config
+        .get("config")
+        .filter(|inner| inner.is_object())
+        .unwrap_or(config);
+    match config.as_object() {
+        Some(map) => {
+            let mut map = map.clone();
+            let warning = normalize_templates_key(&mut map);
+            (JsonValue::Object(map), warning)
+        }
+        None => (config.clone(), None),
+    }
+}
+
+// Sets `text` on a raw `templates` object and records it in `versions`.
+// A config without history first gets its current text saved as `v1`, so
+// the edit can be rolled back. Re-submitting the active text is a no-op.
+pub fn record_template_version(
+    templates: &mut Map<String, JsonValue>,
+    text: String,
+    created_at: Option<u64>,
+) {
+    let current = templates
+        .get("text")
+        .and_then(|v| v.as_str())
+        .map(ToOwned::to_owned);
+    let mut versions = template_versions(templates);
+    if current.as_deref() == Some(text.as_str()) && !versions.is_empty() {
+        return;
+    }
+    if versions.is_empty()
+        && let Some(previous) = current.filter(|previous| *previous != text)
+    {
+        versions.push(TemplateVersion {
+            id: "v1".to_string(),
+            text: previous,
+            created_at: None,
+        });
+    }
+
+    let id = next_version_id(&versions);
+    versions.push(TemplateVersion {
+        id: id.clone(),
+        text: text.clone(),
+        created_at,
+    });
+    while versions.len() > MAX_TEMPLATE_VERSIONS {
+        versions.remove(0);
+    }
+
+    templates.insert("text".to_string(), JsonValue::String(text));
+    templates.insert("active".to_string(), JsonValue::String(id));
+    templates.insert("versions".to_string(), serde_json::json!(versions));
+}
+
+// Points `active`/`text` at an existing version. Returns false (and leaves
+// the object untouched) when `id` is not in the history.
+pub fn rollback_template(templates: &mut Map<String, JsonValue>, id: &str) -> bool {
+    let Some(version) = template_versions(templates)
+        .into_iter()
+        .find(|version| version.id == id)
+    else {
+        return false;
+    };
+    templates.insert("text".to_string(), JsonValue::String(version.text));
+    templates.insert("active".to_string(), JsonValue::String(version.id));
+    true
+}
+
+fn template_versions(templates: &Map<String, JsonValue>) -> Vec<TemplateVersion> {
+    templates
+        .get("versions")
+        .and_then(|versions| serde_json::from_value(versions.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn next_version_id(versions: &[TemplateVersion]) -> String {
+    let last = versions
+        .iter()
+        .filter_map(|version| version.id.strip_prefix('v')?.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("v{}", last + 1)
+}
+
+fn default_output_path() -> String {
+    DEFAULT_OUTPUT_PATH.to_string()
+}
+
+fn default_wrap() -> bool {
+    DEFAULT_WRAP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_config_applies_schema_defaults() {
+        let config = serde_json::json!({ "templates": { "text": "Hello {{name}}" } });
+        let parsed = TemplatesConfig::from_config(&config).expect("parse config");
+
+        assert_eq!(parsed, TemplatesConfig::new("Hello {{name}}"));
+        assert_eq!(parsed.output_path, "text");
+        assert!(parsed.wrap);
+        assert_eq!(parsed.routing_or_default(), "out");
+
+        let schema: JsonValue =
+            serde_json::from_str(include_str!("../schemas/component.schema.json"))
+                .expect("component schema json");
+        let templates = &schema["properties"]["templates"]["properties"];
+        assert_eq!(templates["wrap"]["default"], DEFAULT_WRAP);
+        assert_eq!(templates["output_path"]["default"], DEFAULT_OUTPUT_PATH);
+    }
+
+    #[test]
+    fn from_config_unwraps_legacy_shape_and_round_trips() {
+        let config = serde_json::json!({
+            "component": "ai.greentic.component-templates",
+            "config": {
+                "templates": {
+                    "text": "Hi",
+                    "output_path": "reply.body",
+                    "wrap": false,
+                    "routing": "next"
+                }
+            }
+        });
+        let parsed = TemplatesConfig::from_config(&config).expect("parse config");
+
+        assert_eq!(parsed.output_path, "reply.body");
+        assert!(!parsed.wrap);
+        assert_eq!(parsed.routing_or_default(), "next");
+        assert_eq!(
+            TemplatesConfig::from_config(&parsed.to_config()).expect("round trip"),
+            parsed
+        );
+    }
+
+    #[test]
+    fn builder_validates_and_round_trips() {
+        let config = TemplatesConfig::builder("Hi {{name}}")
+            .output_path("reply.body")
+            .wrap(false)
+            .routing("next")
+            .limits(TemplateLimits {
+                max_bytes: 128,
+                ..TemplateLimits::default()
+            })
+            .build()
+            .expect("valid config");
+        assert_eq!(
+            TemplatesConfig::from_config(&config.to_config()).expect("round trip"),
+            config
+        );
+
+        assert_eq!(
+            TemplatesConfig::builder("Hi").output_path(" ").build(),
+            Err(ConfigError::EmptyOutputPath)
+        );
+        assert_eq!(
+            TemplatesConfig::builder("Hi").routing("").build(),
+            Err(ConfigError::EmptyRouting)
+        );
+        assert!(matches!(
+            TemplatesConfig::builder("{{#if a}}").build(),
+            Err(ConfigError::InvalidTemplate { .. })
+        ));
+        assert!(
+            TemplatesConfig::builder("{{#> layout}}{{{{raw}}}}{{x}}{{{{/raw}}}}{{/layout}}")
+                .build()
+                .is_ok()
+        );
+        let too_large = TemplatesConfig::builder("x".repeat(10))
+            .limits(TemplateLimits {
+                max_bytes: 4,
+                ..TemplateLimits::default()
+            })
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            too_large,
+            ConfigError::Limit(LimitViolation::TooLarge {
+                bytes: 10,
+                limit: 4
+            })
+        ));
+        assert!(too_large.to_string().starts_with("CFG_TEMPLATE_TOO_LARGE"));
+    }
+
+    #[test]
+    fn from_config_rejects_missing_text() {
+        assert!(TemplatesConfig::from_config(&serde_json::json!({})).is_err());
+        assert!(
+            TemplatesConfig::from_config(&serde_json::json!({ "templates": { "wrap": 1 } }))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn record_template_version_seeds_history_and_rolls_back() {
+        let mut templates = Map::new();
+        templates.insert("text".to_string(), serde_json::json!("Old"));
+
+        record_template_version(&mut templates, "New".to_string(), Some(10));
+        record_template_version(&mut templates, "New".to_string(), Some(11));
+        let parsed =
+            TemplatesConfig::from_config(&serde_json::json!({ "templates": templates.clone() }))
+                .expect("parse config");
+        assert_eq!(parsed.text, "New");
+        assert_eq!(parsed.active.as_deref(), Some("v2"));
+        assert_eq!(
+            parsed.versions,
+            [
+                TemplateVersion {
+                    id: "v1".to_string(),
+                    text: "Old".to_string(),
+                    created_at: None,
+                },
+                TemplateVersion {
+                    id: "v2".to_string(),
+                    text: "New".to_string(),
+                    created_at: Some(10),
+                },
+            ]
+        );
+
+        assert!(rollback_template(&mut templates, "v1"));
+        assert_eq!(templates["text"], "Old");
+        assert_eq!(templates["active"], "v1");
+        assert!(!rollback_template(&mut templates, "v9"));
+        assert_eq!(templates["active"], "v1");
+
+        record_template_version(&mut templates, "Newer".to_string(), None);
+        assert_eq!(templates["active"], "v3");
+    }
+
+    #[test]
+    fn record_template_version_caps_history() {
+        let mut templates = Map::new();
+        for n in 0..MAX_TEMPLATE_VERSIONS + 5 {
+            record_template_version(&mut templates, format!("t{n}"), None);
+        }
+        let versions = template_versions(&templates);
+        assert_eq!(versions.len(), MAX_TEMPLATE_VERSIONS);
+        assert_eq!(versions.last().map(|v| v.id.as_str()), Some("v25"));
+        assert_eq!(templates["active"], "v25");
+    }
+
+    #[test]
+    fn templates_key_aliases_are_renamed_with_a_warning() {
+        let config = serde_json::json!({ "Templates": { "text": "Hi" } });
+        assert_eq!(
+            TemplatesConfig::from_config(&config)
+                .expect("alias parses")
+                .text,
+            "Hi"
+        );
+
+        let mut map = serde_json::json!({ "template": { "text": "Hi" }, "other": 1 })
+            .as_object()
+            .cloned()
+            .unwrap();
+        let warning = normalize_templates_key(&mut map).expect("warning");
+        assert!(warning.contains("`template` is deprecated"), "{warning}");
+        assert_eq!(map["templates"]["text"], "Hi");
+        assert_eq!(map["other"], 1);
+
+        let mut map = serde_json::json!({ "templates": { "text": "A" }, "TEMPLATES": {} })
+            .as_object()
+            .cloned()
+            .unwrap();
+        let warning = normalize_templates_key(&mut map).expect("warning");
+        assert!(warning.contains("ignored `TEMPLATES`"), "{warning}");
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["templates"]["text"], "A");
+
+        let mut map = serde_json::json!({ "templates": {} })
+            .as_object()
+            .cloned()
+            .unwrap();
+        assert_eq!(normalize_templates_key(&mut map), None);
+    }
+}
@@ -0,0 +1,101 @@
+use serde_json::{Value as JsonValue, json};
+
+use crate::codec::WireEncoding;
+use crate::{COMPONENT_NAME, COMPONENT_ORG, COMPONENT_VERSION, i18n, operations};
+
+// Runtime capabilities beyond the operation list: syntax-checked template
+// answers, enforced `templates.limits`, version history with rollback, and
+// the `en-XA` pseudo-locale.
+const FEATURES: &[&str] = &[
+    "template_validation",
+    "template_limits",
+    "template_versions",
+    "pseudo_locale",
+];
+
+// Template engines and the built-in helpers templates may call without the
+// host registering them.
+const ENGINES: &[(&str, &[&str])] = &[(
+    "handlebars",
+    &["each", "if", "lookup", "log", "unless", "with"],
+)];
+
+// What exactly is deployed: operators call `run` with
+// `{ "$operation": "diagnostics" }` to compare environments.
+pub fn diagnostics_payload() -> JsonValue {
+    json!({
+        "component": format!("{COMPONENT_ORG}.{COMPONENT_NAME}"),
+        "version": COMPONENT_VERSION,
+        "build": crate::build_metadata(),
+        "features": FEATURES,
+        "engines": ENGINES
+            .iter()
+            .map(|(name, helpers)| json!({ "name": name, "helpers": helpers }))
+            .collect::<Vec<_>>(),
+        "operations": operations::OPERATIONS
+            .iter()
+            .map(|operation| operation.id)
+            .collect::<Vec<_>>(),
+        "wire_encodings": WireEncoding::ALL,
+        "locale_count": i18n::locale_count(),
+        "i18n_bundle_hash": i18n::bundle_hash(),
+        "limits": manifest_limits(),
+        "template_limits": crate::limits::TemplateLimits::default(),
+    })
+}
+
+pub(crate) fn diagnostics_output(_input: &JsonValue) -> JsonValue {
+    diagnostics_payload()
+}
+
+// Host limits declared in component.manifest.json (`memory_mb`, ...).
+fn manifest_limits() -> JsonValue {
+    serde_json::from_str::<JsonValue>(include_str!("../component.manifest.json"))
+        .ok()
+        .and_then(|manifest| manifest.get("limits").cloned())
+        .unwrap_or_else(|| json!({}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_reports_build_and_bundle_facts() {
+        let payload = crate::run_output(&json!({ "$operation": "diagnostics" }));
+
+        assert_eq!(payload, diagnostics_payload());
+        assert_eq!(payload["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(payload["i18n_bundle_hash"], i18n::bundle_hash());
+        assert_eq!(payload["locale_count"], i18n::locale_count());
+        assert_eq!(
+            payload["wire_encodings"],
+            json!(["cbor", "json", "msgpack"])
+        );
+        assert!(payload["limits"]["memory_mb"].is_u64());
+        assert!(
+            payload["features"]
+                .as_array()
+                .expect("features")
+                .contains(&json!("template_versions"))
+        );
+        assert_eq!(payload["engines"][0]["name"], "handlebars");
+        assert!(
+            payload["engines"][0]["helpers"]
+                .as_array()
+                .expect("helpers")
+                .contains(&json!("each"))
+        );
+        assert_eq!(
+            payload["operations"],
+            json!([
+                "handle_message",
+                "diagnostics",
+                "verify_schema_hash",
+                "ast",
+                "schemas",
+                "qa_spec"
+            ])
+        );
+    }
+}
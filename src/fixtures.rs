@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::codec::{self, WireEncoding};
+
+// Conformance fixtures shipped with the crate: `fixtures/<name>.json`, each
+// one `run` invocation with the exact wire bytes a host must send and receive.
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub encoding: WireEncoding,
+    pub input: JsonValue,
+    pub input_hex: String,
+    pub output: JsonValue,
+    pub output_hex: String,
+}
+
+impl Fixture {
+    // Builds a fixture from an input document, recording the bytes this
+    // component produces for it today.
+    pub fn record(encoding: WireEncoding, input: JsonValue) -> Result<Self, String> {
+        let input_bytes = codec::encode(encoding, &input)?;
+        let output_bytes = crate::run_bytes(&input_bytes);
+        Ok(Self {
+            encoding,
+            output: codec::decode(&output_bytes).1,
+            input,
+            input_hex: to_hex(&input_bytes),
+            output_hex: to_hex(&output_bytes),
+        })
+    }
+}
+
+pub fn fixture_path(name: &str) -> PathBuf {
+    Path::new(FIXTURES_DIR).join(format!("{name}.json"))
+}
+
+// Fixture names (file stems), sorted.
+pub fn fixture_names() -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(FIXTURES_DIR).map_err(|err| format!("{FIXTURES_DIR}: {err}"))?;
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        if path.extension().is_some_and(|ext| ext == "json")
+            && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+        {
+            names.push(stem.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+pub fn load_fixture(name: &str) -> Result<Fixture, String> {
+    let path = fixture_path(name);
+    let text = fs::read_to_string(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+    serde_json::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+// Replays one fixture and checks every recorded artifact byte-for-byte:
+// the input document encodes to `input_hex`, `run` answers `output_hex`,
+// and that answer decodes to `output`.
+pub fn run_fixture(name: &str) -> Result<(), String> {
+    let fixture = load_fixture(name)?;
+    let input_bytes = codec::encode(fixture.encoding, &fixture.input)?;
+    if to_hex(&input_bytes) != fixture.input_hex {
+        return Err(format!("{name}: input does not encode to input_hex"));
+    }
+    let output_bytes = crate::run_bytes(&input_bytes);
+    if to_hex(&output_bytes) != fixture.output_hex {
+        return Err(format!(
+            "{name}: output bytes {} differ from output_hex",
+            to_hex(&output_bytes)
+        ));
+    }
+    let (encoding, output) = codec::decode(&output_bytes);
+    if encoding != fixture.encoding || output != fixture.output {
+        return Err(format!("{name}: output decodes to {output}"));
+    }
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_fixture_replays() {
+        let fixture =
+            Fixture::record(WireEncoding::Cbor, serde_json::json!({ "input": "hi" })).unwrap();
+        assert_eq!(fixture.input_hex, "a165696e707574626869");
+        assert_eq!(fixture.output, crate::run_output(&fixture.input));
+    }
+}
@@ -1,6 +1,8 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::OnceLock;
 
+use serde::Serialize;
+
 use crate::i18n_bundle::{LocaleBundle, unpack_locales_from_cbor};
 
 // Generated by build.rs: static embedded CBOR translation bundle.
@@ -13,29 +15,309 @@ fn bundle() -> &'static LocaleBundle {
     I18N_BUNDLE.get_or_init(|| unpack_locales_from_cbor(I18N_BUNDLE_CBOR).unwrap_or_default())
 }
 
+// Regional hops taken before truncating subtags, e.g. Brazilian users see
+// European Portuguese before the bare `pt` catalog. This compiled-in table is
+// the only one `t` and `resolve_supported` use; it is not read from config.
+// Extend it here as catalogs grow.
+pub const DEFAULT_FALLBACKS: &[(&str, &str)] = &[
+    ("pt-BR", "pt-PT"),
+    ("pt-AO", "pt-PT"),
+    ("pt-MZ", "pt-PT"),
+    ("en-AU", "en-GB"),
+    ("en-NZ", "en-GB"),
+    ("en-IE", "en-GB"),
+    ("en-IN", "en-GB"),
+    ("zh-TW", "zh-Hant-TW"),
+    ("zh-HK", "zh-Hant-HK"),
+    ("zh-MO", "zh-Hant-MO"),
+    ("zh-CN", "zh-Hans-CN"),
+    ("zh-SG", "zh-Hans-SG"),
+];
+
+// Legacy or alternate language codes -> canonical subtag. Channel metadata
+// still carries several of these; catalogs may be filed under either side.
+pub const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("iw", "he"),
+    ("in", "id"),
+    ("ji", "yi"),
+    ("jw", "jv"),
+    ("mo", "ro"),
+    ("no", "nb"),
+    ("tl", "fil"),
+];
+
+// Canonicalizes a locale tag: `_` -> `-`, language lowercase, script
+// titlecase, region uppercase, and legacy language codes aliased.
+pub fn normalize(locale: &str) -> String {
+    locale
+        .trim()
+        .split(['-', '_'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .enumerate()
+        .map(|(index, part)| {
+            if index == 0 {
+                let lower = part.to_ascii_lowercase();
+                LANGUAGE_ALIASES
+                    .iter()
+                    .find(|(legacy, _)| *legacy == lower)
+                    .map(|(_, canonical)| (*canonical).to_string())
+                    .unwrap_or(lower)
+            } else if part.len() == 4 && part.chars().all(|c| c.is_ascii_alphabetic()) {
+                let mut chars = part.chars();
+                let first = chars.next().map(|c| c.to_ascii_uppercase());
+                first
+                    .into_iter()
+                    .chain(chars.map(|c| c.to_ascii_lowercase()))
+                    .collect()
+            } else if part.len() == 2 || part.len() == 3 {
+                part.to_ascii_uppercase()
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Returns the first tag in the fallback chain that has an embedded catalog.
+pub fn resolve_supported(locale: &str) -> String {
+    locale_chain(locale)
+        .into_iter()
+        .find(|candidate| bundle().contains_key(candidate))
+        .unwrap_or_else(|| "en".to_string())
+}
+
 // Fallback precedence is deterministic:
-// exact locale -> base language -> en
+// exact locale -> table hop or parent tag (repeated) -> en
 fn locale_chain(locale: &str) -> Vec<String> {
-    let normalized = locale.replace('_', "-");
-    let mut chain = vec![normalized.clone()];
-    if let Some((base, _)) = normalized.split_once('-') {
-        chain.push(base.to_string());
+    locale_chain_with(locale, DEFAULT_FALLBACKS)
+}
+
+// Every canonical tag is followed by its legacy spellings (`nb` -> `no`), so
+// catalogs filed under the legacy code keep resolving after normalization.
+fn with_legacy_spellings(tag: String) -> Vec<String> {
+    let (language, rest) = match tag.split_once('-') {
+        Some((language, rest)) => (language.to_string(), Some(rest.to_string())),
+        None => (tag.clone(), None),
+    };
+    let mut tags = vec![tag];
+    for (legacy, _) in LANGUAGE_ALIASES
+        .iter()
+        .filter(|(_, canonical)| *canonical == language)
+    {
+        tags.push(match &rest {
+            Some(rest) => format!("{legacy}-{rest}"),
+            None => (*legacy).to_string(),
+        });
+    }
+    tags
+}
+
+// Walks `fallbacks` first, otherwise drops the last subtag:
+// pt-BR -> pt-PT -> pt -> en, zh-Hant-TW -> zh-Hant -> zh -> en.
+// Tags already visited end the walk, so cyclic tables still terminate.
+// Takes the table as an argument so candidate entries for
+// `DEFAULT_FALLBACKS` can be checked (see tests/i18n_properties.rs).
+pub fn locale_chain_with(locale: &str, fallbacks: &[(&str, &str)]) -> Vec<String> {
+    let mut walked: Vec<String> = Vec::new();
+    let mut current = Some(normalize(locale));
+    while let Some(tag) = current.take() {
+        if tag.is_empty() || walked.contains(&tag) {
+            break;
+        }
+        current = fallbacks
+            .iter()
+            .find(|(from, _)| normalize(from) == tag)
+            .map(|(_, to)| normalize(to))
+            .or_else(|| tag.rsplit_once('-').map(|(parent, _)| parent.to_string()));
+        walked.push(tag);
+    }
+
+    let mut chain: Vec<String> = Vec::new();
+    for tag in walked.into_iter().flat_map(with_legacy_spellings) {
+        if !chain.contains(&tag) {
+            chain.push(tag);
+        }
+    }
+    if !chain.iter().any(|tag| tag == "en") {
+        chain.push("en".to_string());
     }
-    chain.push("en".to_string());
     chain
 }
 
+// Locale -> (key -> replacement text), supplied by the embedding host.
+pub type I18nOverrides = BTreeMap<String, BTreeMap<String, String>>;
+
 // Translation lookup function used throughout generated QA/setup code.
 // Extend by adding pluralization/context handling if your component needs it.
 pub fn t(locale: &str, key: &str) -> String {
-    for candidate in locale_chain(locale) {
-        if let Some(map) = bundle().get(&candidate)
-            && let Some(value) = map.get(key)
-        {
-            return value.clone();
+    t_with_overrides(&I18nOverrides::new(), locale, key)
+}
+
+// Same fallback chain as `t`, but each locale step checks tenant overrides
+// before the embedded catalog, so a rebrand in `en` does not mask `fr`.
+pub fn t_with_overrides(overrides: &I18nOverrides, locale: &str, key: &str) -> String {
+    t_with_fallbacks(overrides, DEFAULT_FALLBACKS, locale, key)
+}
+
+// `t_with_overrides` with a host-supplied regional fallback table in place
+// of `DEFAULT_FALLBACKS` (e.g. routing `es-MX` through `es-419`).
+pub fn t_with_fallbacks(
+    overrides: &I18nOverrides,
+    fallbacks: &[(&str, &str)],
+    locale: &str,
+    key: &str,
+) -> String {
+    ResolvedLocale::with_fallbacks(overrides, fallbacks, locale).t(key)
+}
+
+// Locale resolution done once per invocation: the fallback chain is
+// normalized and trimmed to steps that have a catalog or overrides, so
+// repeated lookups (one per helper call) are plain map reads.
+#[derive(Debug, Clone)]
+pub struct ResolvedLocale<'a> {
+    overrides: &'a I18nOverrides,
+    chain: Vec<String>,
+    pseudo: bool,
+}
+
+impl<'a> ResolvedLocale<'a> {
+    pub fn new(overrides: &'a I18nOverrides, locale: &str) -> Self {
+        Self::with_fallbacks(overrides, DEFAULT_FALLBACKS, locale)
+    }
+
+    pub fn with_fallbacks(
+        overrides: &'a I18nOverrides,
+        fallbacks: &[(&str, &str)],
+        locale: &str,
+    ) -> Self {
+        let pseudo = normalize(locale) == PSEUDO_LOCALE;
+        let chain = locale_chain_with(if pseudo { "en" } else { locale }, fallbacks)
+            .into_iter()
+            .filter(|tag| overrides.contains_key(tag) || bundle().contains_key(tag))
+            .collect();
+        Self {
+            overrides,
+            chain,
+            pseudo,
+        }
+    }
+
+    // Fallback tags that will actually be consulted, most specific first.
+    pub fn chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    pub fn t(&self, key: &str) -> String {
+        let text = self
+            .chain
+            .iter()
+            .find_map(|tag| {
+                self.overrides
+                    .get(tag)
+                    .and_then(|map| map.get(key))
+                    .or_else(|| bundle().get(tag).and_then(|map| map.get(key)))
+            })
+            .cloned()
+            .unwrap_or_else(|| key.to_string());
+        if self.pseudo {
+            pseudolocalize(&text)
+        } else {
+            text
+        }
+    }
+}
+
+// Synthetic QA locale: English text, accented and elongated on the fly.
+// Anything that still renders as plain ASCII was never routed through `t`.
+pub const PSEUDO_LOCALE: &str = "en-XA";
+
+// `Template text` -> `[Ţééɱƥļààţéé ţééẋţ]`. Vowels are doubled to simulate
+// the ~30% growth of most translations; `{placeholder}` and `{{handlebars}}`
+// spans are copied verbatim so substitution keeps working.
+pub fn pseudolocalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2 + 2);
+    out.push('[');
+    let mut depth = 0usize;
+    for ch in text.chars() {
+        match ch {
+            '{' => {
+                depth += 1;
+                out.push(ch);
+            }
+            '}' => {
+                depth = depth.saturating_sub(1);
+                out.push(ch);
+            }
+            _ if depth > 0 => out.push(ch),
+            _ => {
+                let accented = accent(ch);
+                out.push(accented);
+                if "aeiouAEIOU".contains(ch) {
+                    out.push(accented);
+                }
+            }
         }
     }
-    key.to_string()
+    out.push(']');
+    out
+}
+
+fn accent(ch: char) -> char {
+    match ch {
+        'a' => 'à',
+        'b' => 'ƀ',
+        'c' => 'ç',
+        'd' => 'ð',
+        'e' => 'é',
+        'g' => 'ĝ',
+        'h' => 'ĥ',
+        'i' => 'î',
+        'j' => 'ĵ',
+        'k' => 'ķ',
+        'l' => 'ļ',
+        'm' => 'ɱ',
+        'n' => 'ñ',
+        'o' => 'ö',
+        'p' => 'ƥ',
+        'r' => 'ŕ',
+        's' => 'š',
+        't' => 'ţ',
+        'u' => 'û',
+        'w' => 'ŵ',
+        'x' => 'ẋ',
+        'y' => 'ý',
+        'z' => 'ž',
+        'A' => 'Å',
+        'C' => 'Ç',
+        'D' => 'Ð',
+        'E' => 'É',
+        'G' => 'Ĝ',
+        'H' => 'Ĥ',
+        'I' => 'Î',
+        'L' => 'Ļ',
+        'N' => 'Ñ',
+        'O' => 'Ö',
+        'R' => 'Ŕ',
+        'S' => 'Š',
+        'T' => 'Ţ',
+        'U' => 'Û',
+        'W' => 'Ŵ',
+        'Y' => 'Ý',
+        'Z' => 'Ž',
+        other => other,
+    }
+}
+
+// Content hash of the embedded bundle (`blake3:<hex>`), computed by build.rs.
+pub fn bundle_hash() -> &'static str {
+    I18N_BUNDLE_HASH
+}
+
+// Embedded catalogs, `en` included.
+pub fn locale_count() -> usize {
+    bundle().len()
 }
 
 // Returns canonical source key list (from `en`).
@@ -46,7 +328,287 @@ pub fn all_keys() -> Vec<String> {
     en.keys().cloned().collect()
 }
 
+// Localization gaps of one embedded catalog relative to `en`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct I18nReport {
+    pub locale: String,
+    pub catalog_present: bool,
+    pub missing: Vec<String>,
+    pub empty: Vec<String>,
+}
+
+impl I18nReport {
+    pub fn is_complete(&self) -> bool {
+        self.catalog_present && self.missing.is_empty() && self.empty.is_empty()
+    }
+}
+
+// Diffs the embedded catalog for `locale` (exact tag after normalization,
+// no fallback) against `en`, so tooling can list untranslated keys.
+pub fn i18n_report(locale: &str) -> I18nReport {
+    let locale = normalize(locale);
+    // `no`/`tl` catalogs are filed under the legacy code `normalize` rewrites.
+    let catalog = with_legacy_spellings(locale.clone())
+        .into_iter()
+        .find_map(|tag| bundle().get(&tag));
+    let mut missing = Vec::new();
+    let mut empty = Vec::new();
+    for key in all_keys() {
+        match catalog.and_then(|map| map.get(&key)) {
+            None => missing.push(key),
+            Some(value) if value.trim().is_empty() => empty.push(key),
+            Some(_) => {}
+        }
+    }
+    I18nReport {
+        locale,
+        catalog_present: catalog.is_some(),
+        missing,
+        empty,
+    }
+}
+
+// Translation coverage of one embedded catalog relative to `en`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LocaleCoverage {
+    pub locale: String,
+    pub total_keys: usize,
+    pub translated_keys: usize,
+    pub placeholder_mismatches: Vec<PlaceholderMismatch>,
+}
+
+// A translated value whose `{placeholder}` names differ from `en`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlaceholderMismatch {
+    pub key: String,
+    pub expected: BTreeSet<String>,
+    pub found: BTreeSet<String>,
+}
+
+// Per-locale key counts and placeholder drift for every embedded catalog.
+pub fn coverage() -> Vec<LocaleCoverage> {
+    let en = en_messages();
+    bundle()
+        .iter()
+        .map(|(locale, catalog)| {
+            let mut translated_keys = 0;
+            let mut placeholder_mismatches = Vec::new();
+            for (key, source) in &en {
+                let Some(value) = catalog.get(key).filter(|value| !value.trim().is_empty()) else {
+                    continue;
+                };
+                translated_keys += 1;
+                let expected = placeholders(source);
+                let found = placeholders(value);
+                if expected != found {
+                    placeholder_mismatches.push(PlaceholderMismatch {
+                        key: key.clone(),
+                        expected,
+                        found,
+                    });
+                }
+            }
+            LocaleCoverage {
+                locale: locale.clone(),
+                total_keys: en.len(),
+                translated_keys,
+                placeholder_mismatches,
+            }
+        })
+        .collect()
+}
+
+// Names referenced as `{name}` or `{{name}}` in a catalog value.
+pub fn placeholders(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let after = rest[start..].trim_start_matches('{');
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() && !name.contains('{') {
+            names.insert(name.to_string());
+        }
+        rest = after[end..].trim_start_matches('}');
+    }
+    names
+}
+
 // Returns English dictionary for diagnostics/tests/tools.
 pub fn en_messages() -> BTreeMap<String, String> {
     bundle().get("en").cloned().unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolved_locale_matches_per_call_lookup() {
+        let overrides = I18nOverrides::from([(
+            "pt".to_string(),
+            BTreeMap::from([("qa.title".to_string(), "Modelos".to_string())]),
+        )]);
+        for locale in ["pt-BR", "de-AT", "en-XA", "xx"] {
+            let resolved = ResolvedLocale::new(&overrides, locale);
+            assert_eq!(resolved.chain().last().map(String::as_str), Some("en"));
+            for key in ["qa.title", "component.display_name", "missing.key"] {
+                assert_eq!(resolved.t(key), t_with_overrides(&overrides, locale, key));
+            }
+        }
+        assert_eq!(
+            ResolvedLocale::new(&overrides, "pt-BR").t("qa.title"),
+            "Modelos"
+        );
+    }
+
+    #[test]
+    fn locale_chain_walks_regional_and_script_parents() {
+        assert_eq!(locale_chain("pt-BR"), ["pt-BR", "pt-PT", "pt", "en"]);
+        assert_eq!(
+            locale_chain("zh-Hant-TW"),
+            ["zh-Hant-TW", "zh-Hant", "zh", "en"]
+        );
+        assert_eq!(locale_chain("nl_NL"), ["nl-NL", "nl", "en"]);
+        assert_eq!(locale_chain("en-AU"), ["en-AU", "en-GB", "en"]);
+    }
+
+    #[test]
+    fn normalize_canonicalizes_case_separators_and_aliases() {
+        assert_eq!(normalize("pt_br"), "pt-BR");
+        assert_eq!(normalize("ZH-hant-tw"), "zh-Hant-TW");
+        assert_eq!(normalize("iw-IL"), "he-IL");
+        assert_eq!(normalize("in"), "id");
+        assert_eq!(normalize(" es-419 "), "es-419");
+        assert_eq!(normalize("fr_ -CA"), "fr-CA");
+    }
+
+    #[test]
+    fn legacy_codes_resolve_to_shipped_catalogs() {
+        assert_eq!(resolve_supported("in-ID"), "id");
+        assert_eq!(resolve_supported("no"), "no");
+        assert_eq!(resolve_supported("nb-NO"), "no");
+        assert!(i18n_report("nb").catalog_present);
+        assert_eq!(resolve_supported("fil"), "tl");
+        assert_eq!(resolve_supported("iw"), "en");
+        assert_eq!(locale_chain("no"), ["nb", "no", "en"]);
+    }
+
+    #[test]
+    fn pseudo_locale_accents_text_but_keeps_placeholders() {
+        assert_eq!(pseudolocalize("Hi {name}"), "[Ĥîî {name}]");
+        assert_eq!(pseudolocalize("Hello {{name}}"), "[Ĥééļļöö {{name}}]");
+        assert_eq!(
+            t("en-XA", "qa.text.label"),
+            pseudolocalize(&t("en", "qa.text.label"))
+        );
+        assert_eq!(t("en_xa", "missing.key"), "[ɱîîššîîñĝ.ķééý]");
+    }
+
+    #[test]
+    fn i18n_report_lists_keys_missing_from_a_catalog() {
+        let report = i18n_report("fr");
+        let fr = &bundle()["fr"];
+        assert!(report.catalog_present);
+        for key in all_keys() {
+            assert_eq!(
+                report.missing.contains(&key),
+                !fr.contains_key(&key),
+                "{key}"
+            );
+        }
+
+        let report = i18n_report("xx-YY");
+        assert_eq!(report.locale, "xx-YY");
+        assert!(!report.catalog_present);
+        assert_eq!(report.missing, all_keys());
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn untranslated_keys_fall_back_to_en_and_show_as_gaps() {
+        let report = i18n_report("fr");
+        for key in [
+            "component.operation.verify_schema_hash",
+            "config.templates.text.title",
+            "qa.rollback.label",
+        ] {
+            assert_eq!(t("fr", key), t("en", key));
+            assert!(report.missing.iter().any(|gap| gap == key), "{key}");
+        }
+    }
+
+    #[test]
+    fn placeholders_extracts_single_and_double_brace_names() {
+        let names = placeholders("use `{supported}` not {operation}; hi {{ name }}");
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            ["name", "operation", "supported"]
+        );
+        assert!(placeholders("no braces").is_empty());
+        assert!(placeholders("dangling {open").is_empty());
+    }
+
+    #[test]
+    fn coverage_reports_every_catalog_against_en() {
+        let report = coverage();
+        let en = report
+            .iter()
+            .find(|entry| entry.locale == "en")
+            .expect("en coverage");
+        assert_eq!(en.translated_keys, en.total_keys);
+        assert_eq!(report.len(), bundle().len());
+    }
+
+    #[test]
+    fn locale_chain_with_custom_table_terminates_on_cycles() {
+        let table = [("es-MX", "es-419"), ("es-419", "es-MX")];
+        assert_eq!(
+            locale_chain_with("es-MX", &table),
+            ["es-MX", "es-419", "en"]
+        );
+    }
+
+    #[test]
+    fn custom_fallback_table_reaches_override_lookups() {
+        let overrides = I18nOverrides::from([(
+            "es-419".to_string(),
+            BTreeMap::from([("qa.title".to_string(), "Plantilla 419".to_string())]),
+        )]);
+        let table = [("es-MX", "es-419")];
+        assert_eq!(
+            t_with_fallbacks(&overrides, &table, "es-MX", "qa.title"),
+            "Plantilla 419"
+        );
+        assert_ne!(
+            t_with_overrides(&overrides, "es-MX", "qa.title"),
+            "Plantilla 419"
+        );
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_embedded_catalog() {
+        let overrides = I18nOverrides::from([(
+            "en".to_string(),
+            BTreeMap::from([(
+                "component.display_name".to_string(),
+                "Advisor Templates".to_string(),
+            )]),
+        )]);
+
+        assert_eq!(
+            t_with_overrides(&overrides, "en", "component.display_name"),
+            "Advisor Templates"
+        );
+        assert_eq!(
+            t_with_overrides(&overrides, "en-GB", "component.display_name"),
+            t("en-GB", "component.display_name")
+        );
+        assert_eq!(
+            t_with_overrides(&overrides, "fr", "component.display_name"),
+            t("fr", "component.display_name")
+        );
+    }
+}
@@ -30,14 +30,51 @@ pub fn load_locale_files(dir: &Path) -> Result<LocaleBundle, String> {
             continue;
         }
         let raw = fs::read_to_string(&path).map_err(|err| err.to_string())?;
-        let map: BTreeMap<String, String> =
-            serde_json::from_str(&raw).map_err(|err| err.to_string())?;
+        let value: serde_json::Value =
+            serde_json::from_str(&raw).map_err(|err| format!("{}: {err}", path.display()))?;
+        let map = flatten_catalog(&value).map_err(|err| format!("{}: {err}", path.display()))?;
         locales.insert(stem.to_string(), map);
     }
 
     Ok(locales)
 }
 
+// Flattens a (possibly nested) catalog object into dotted keys:
+// { "qa": { "text": { "label": "..." } } } -> { "qa.text.label": "..." }.
+// Flat files pass through unchanged, so both layouts can coexist.
+pub fn flatten_catalog(value: &serde_json::Value) -> Result<BTreeMap<String, String>, String> {
+    let serde_json::Value::Object(map) = value else {
+        return Err("catalog must be a JSON object".to_string());
+    };
+    let mut flat = BTreeMap::new();
+    flatten_into(&mut flat, "", map)?;
+    Ok(flat)
+}
+
+fn flatten_into(
+    flat: &mut BTreeMap<String, String>,
+    prefix: &str,
+    map: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    for (key, value) in map {
+        let full_key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            serde_json::Value::String(text) => {
+                if flat.insert(full_key.clone(), text.clone()).is_some() {
+                    return Err(format!("duplicate key `{full_key}`"));
+                }
+            }
+            serde_json::Value::Object(nested) => flatten_into(flat, &full_key, nested)?,
+            _ => return Err(format!("key `{full_key}` must be a string or an object")),
+        }
+    }
+    Ok(())
+}
+
 // Produces canonical CBOR bytes for reproducible build embedding.
 pub fn pack_locales_to_cbor(locales: &LocaleBundle) -> Result<Vec<u8>, String> {
     canonical::to_canonical_cbor_allow_floats(locales).map_err(|err| err.to_string())
@@ -65,4 +102,29 @@ mod tests {
 
         assert!(decoded.contains_key("en"));
     }
+
+    #[test]
+    fn nested_catalog_flattens_to_dotted_keys() {
+        let catalog = serde_json::json!({
+            "component.display_name": "Templates",
+            "qa": {
+                "text": { "label": "Template text" },
+                "title": "Templates configuration"
+            }
+        });
+
+        let flat = flatten_catalog(&catalog).expect("flatten catalog");
+        assert_eq!(flat["component.display_name"], "Templates");
+        assert_eq!(flat["qa.text.label"], "Template text");
+        assert_eq!(flat["qa.title"], "Templates configuration");
+    }
+
+    #[test]
+    fn nested_catalog_rejects_colliding_and_non_string_keys() {
+        let colliding = serde_json::json!({ "qa.title": "a", "qa": { "title": "b" } });
+        assert!(flatten_catalog(&colliding).is_err());
+
+        let numeric = serde_json::json!({ "qa": { "count": 3 } });
+        assert!(flatten_catalog(&numeric).is_err());
+    }
 }
@@ -0,0 +1,226 @@
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use serde_json::{Map, Value as JsonValue, json};
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// Draft 2020-12 rendering of the `run` input contract.
+pub fn input_json_schema() -> JsonValue {
+    to_json_schema(&crate::input_schema_ir())
+}
+
+// Draft 2020-12 rendering of the `run` output contract.
+pub fn output_json_schema() -> JsonValue {
+    to_json_schema(&crate::output_schema_ir())
+}
+
+// Draft 2020-12 rendering of the component config contract.
+pub fn config_json_schema() -> JsonValue {
+    to_json_schema(&crate::config_schema_ir())
+}
+
+// `run` form: `{ "$operation": "schemas" }`. Returns all three contracts so
+// hosts without a SchemaIr reader can validate against the component.
+pub(crate) fn schemas_output(_input: &JsonValue) -> JsonValue {
+    json!({
+        "input": input_json_schema(),
+        "output": output_json_schema(),
+        "config": config_json_schema(),
+    })
+}
+
+// Root document: the converted schema plus the `$schema` dialect marker.
+pub fn to_json_schema(schema: &SchemaIr) -> JsonValue {
+    let mut root = convert(schema);
+    if let JsonValue::Object(map) = &mut root {
+        map.insert(
+            "$schema".to_string(),
+            JsonValue::String(JSON_SCHEMA_DIALECT.to_string()),
+        );
+    }
+    root
+}
+
+// Converts one IR node. Unset bounds are omitted rather than emitted as null,
+// so validators that reject unknown keyword values stay happy.
+fn convert(schema: &SchemaIr) -> JsonValue {
+    match schema {
+        SchemaIr::Object {
+            properties,
+            required,
+            additional,
+        } => {
+            let mut map = Map::new();
+            map.insert("type".to_string(), json!("object"));
+            map.insert(
+                "properties".to_string(),
+                JsonValue::Object(
+                    properties
+                        .iter()
+                        .map(|(name, property)| (name.clone(), convert(property)))
+                        .collect(),
+                ),
+            );
+            if !required.is_empty() {
+                map.insert("required".to_string(), json!(required));
+            }
+            map.insert(
+                "additionalProperties".to_string(),
+                match additional {
+                    AdditionalProperties::Allow => JsonValue::Bool(true),
+                    AdditionalProperties::Forbid => JsonValue::Bool(false),
+                    AdditionalProperties::Schema(schema) => convert(schema),
+                },
+            );
+            JsonValue::Object(map)
+        }
+        SchemaIr::Array {
+            items,
+            min_items,
+            max_items,
+        } => {
+            let mut map = Map::new();
+            map.insert("type".to_string(), json!("array"));
+            map.insert("items".to_string(), convert(items));
+            insert_some(&mut map, "minItems", min_items.map(JsonValue::from));
+            insert_some(&mut map, "maxItems", max_items.map(JsonValue::from));
+            JsonValue::Object(map)
+        }
+        SchemaIr::String {
+            min_len,
+            max_len,
+            regex,
+            format,
+        } => {
+            let mut map = Map::new();
+            map.insert("type".to_string(), json!("string"));
+            insert_some(&mut map, "minLength", min_len.map(JsonValue::from));
+            insert_some(&mut map, "maxLength", max_len.map(JsonValue::from));
+            insert_some(&mut map, "pattern", regex.clone().map(JsonValue::from));
+            insert_some(&mut map, "format", format.clone().map(JsonValue::from));
+            JsonValue::Object(map)
+        }
+        SchemaIr::Int { min, max } => {
+            let mut map = Map::new();
+            map.insert("type".to_string(), json!("integer"));
+            insert_some(&mut map, "minimum", min.map(JsonValue::from));
+            insert_some(&mut map, "maximum", max.map(JsonValue::from));
+            JsonValue::Object(map)
+        }
+        SchemaIr::Float { min, max } => {
+            let mut map = Map::new();
+            map.insert("type".to_string(), json!("number"));
+            insert_some(&mut map, "minimum", min.map(JsonValue::from));
+            insert_some(&mut map, "maximum", max.map(JsonValue::from));
+            JsonValue::Object(map)
+        }
+        SchemaIr::Bool => json!({ "type": "boolean" }),
+        SchemaIr::Null => json!({ "type": "null" }),
+        SchemaIr::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+        SchemaIr::Enum { values } => json!({
+            "enum": values
+                .iter()
+                .map(|value| value.deserialized::<JsonValue>().unwrap_or(JsonValue::Null))
+                .collect::<Vec<_>>()
+        }),
+        SchemaIr::OneOf { variants } => json!({
+            "oneOf": variants.iter().map(convert).collect::<Vec<_>>()
+        }),
+        SchemaIr::Ref { id } => json!({ "$ref": id }),
+    }
+}
+
+fn insert_some(map: &mut Map<String, JsonValue>, key: &str, value: Option<JsonValue>) {
+    if let Some(value) = value {
+        map.insert(key.to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn config_schema_converts_to_draft_2020_12() {
+        let schema = config_json_schema();
+        assert_eq!(schema["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(schema["type"], "object");
+
+        let templates = &schema["properties"]["templates"];
+        assert_eq!(templates["required"], json!(["text"]));
+        assert_eq!(templates["properties"]["text"]["type"], "string");
+        assert_eq!(templates["properties"]["text"]["minLength"], 0);
+        assert!(templates["properties"]["text"].get("maxLength").is_none());
+        assert!(templates["properties"]["text"].get("$schema").is_none());
+    }
+
+    #[test]
+    fn io_schemas_match_operation_contract() {
+        assert_eq!(input_json_schema()["required"], json!(["input"]));
+        assert_eq!(output_json_schema()["required"], json!(["message"]));
+    }
+
+    #[test]
+    fn schemas_operation_returns_all_three_documents() {
+        let output = schemas_output(&json!({ "$operation": "schemas" }));
+        assert_eq!(output["input"], input_json_schema());
+        assert_eq!(output["output"], output_json_schema());
+        assert_eq!(output["config"], config_json_schema());
+        for key in ["input", "output", "config"] {
+            assert_eq!(output[key]["$schema"], JSON_SCHEMA_DIALECT, "{key}");
+        }
+    }
+
+    #[test]
+    fn scalar_enum_and_union_nodes_convert() {
+        let schema = SchemaIr::Object {
+            properties: BTreeMap::from([
+                (
+                    "mode".to_string(),
+                    SchemaIr::Enum {
+                        values: vec![
+                            ciborium::value::Value::Text("a".into()),
+                            ciborium::value::Value::Integer(2.into()),
+                        ],
+                    },
+                ),
+                (
+                    "value".to_string(),
+                    SchemaIr::OneOf {
+                        variants: vec![
+                            SchemaIr::Int {
+                                min: Some(1),
+                                max: None,
+                            },
+                            SchemaIr::Null,
+                        ],
+                    },
+                ),
+                (
+                    "tags".to_string(),
+                    SchemaIr::Array {
+                        items: Box::new(SchemaIr::Bool),
+                        min_items: None,
+                        max_items: Some(3),
+                    },
+                ),
+            ]),
+            required: Vec::new(),
+            additional: AdditionalProperties::Forbid,
+        };
+        let json = to_json_schema(&schema);
+
+        assert_eq!(json["additionalProperties"], false);
+        assert!(json.get("required").is_none());
+        assert_eq!(json["properties"]["mode"]["enum"], json!(["a", 2]));
+        assert_eq!(
+            json["properties"]["value"]["oneOf"],
+            json!([{ "type": "integer", "minimum": 1 }, { "type": "null" }])
+        );
+        assert_eq!(
+            json["properties"]["tags"],
+            json!({ "type": "array", "items": { "type": "boolean" }, "maxItems": 3 })
+        );
+    }
+}
@@ -1,20 +1,10 @@
-#[cfg(target_arch = "wasm32")]
-use std::collections::BTreeMap;
-#[cfg(test)]
 use std::collections::BTreeMap;
 
-#[cfg(target_arch = "wasm32")]
-use greentic_types::cbor::canonical;
-#[cfg(any(target_arch = "wasm32", test))]
+use serde::Serialize;
+
 use greentic_types::i18n_text::I18nText;
-#[cfg(target_arch = "wasm32")]
 use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
-#[cfg(target_arch = "wasm32")]
-use greentic_types::schemas::component::v0_6_0::{
-    ComponentDescribe, ComponentInfo, ComponentOperation, ComponentRunInput, ComponentRunOutput,
-    schema_hash,
-};
-#[cfg(any(target_arch = "wasm32", test))]
+use greentic_types::schemas::component::v0_6_0::{ComponentDescribe, ComponentInfo};
 use greentic_types::schemas::component::v0_6_0::{
     ComponentQaSpec, QaMode as QaModeSpec, Question, QuestionKind,
 };
@@ -32,13 +22,26 @@ use bindings::exports::greentic::component::{
     component_runtime, component_schema,
 };
 
+pub mod ast;
+pub mod codec;
+pub mod config;
+pub mod diagnostics;
+pub mod fixtures;
 pub mod i18n;
 pub mod i18n_bundle;
+pub mod json_schema;
+pub mod limits;
+pub mod operations;
 pub mod qa;
+pub mod validation;
 
 const COMPONENT_NAME: &str = "component-templates";
 const COMPONENT_ORG: &str = "ai.greentic";
 const COMPONENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const COMPONENT_CATEGORY: &str = "formatting";
+const COMPONENT_TAGS: &[&str] = &["template", "text"];
+const COMPONENT_DOCS_URL: &str = env!("CARGO_PKG_REPOSITORY");
+const COMPONENT_ICON: &str = "template";
 
 #[cfg(target_arch = "wasm32")]
 #[used]
@@ -77,19 +80,8 @@ impl component_schema::Guest for Component {
 #[cfg(target_arch = "wasm32")]
 impl component_runtime::Guest for Component {
     fn run(input: Vec<u8>, state: Vec<u8>) -> component_runtime::RunResult {
-        let value = parse_payload(&input);
-        let input_text = value
-            .get("input")
-            .and_then(|v| v.as_str())
-            .map(ToOwned::to_owned)
-            .unwrap_or_else(|| value.to_string());
-
-        let output = serde_json::json!({
-            "message": handle_message("handle_message", &input_text)
-        });
-
         component_runtime::RunResult {
-            output: encode_cbor(&output),
+            output: run_bytes(&input),
             new_state: state,
         }
     }
@@ -104,9 +96,7 @@ impl component_qa::Guest for Component {
 
     fn apply_answers(mode: QaMode, current_config: Vec<u8>, answers: Vec<u8>) -> Vec<u8> {
         let _ = mode;
-        let updated =
-            apply_template_answers(parse_payload(&current_config), parse_payload(&answers));
-        encode_cbor(&updated)
+        apply_answers_bytes(&current_config, &answers)
     }
 }
 
@@ -141,7 +131,293 @@ pub fn handle_message(operation: &str, input: &str) -> String {
     format!("{COMPONENT_NAME}::{operation} => {}", input.trim())
 }
 
-#[cfg(any(target_arch = "wasm32", test))]
+// Output document produced by `run` for a decoded input document. The
+// reserved `$operation` field selects a registered operation; when it is
+// absent the input is a `handle_message` call. A selector naming no
+// operation is an `invalid_input` error, so a typo never runs as a message.
+pub fn run_output(input: &serde_json::Value) -> serde_json::Value {
+    let Some(selector) = input.get(operations::OPERATION_SELECTOR) else {
+        return (operations::default_operation().run)(input);
+    };
+    match selector.as_str().and_then(operations::find) {
+        Some(spec) => (spec.run)(input),
+        None => {
+            let known = operations::OPERATIONS
+                .iter()
+                .map(|operation| operation.id)
+                .collect::<Vec<_>>()
+                .join(", ");
+            codec::error_document(
+                greentic_types::ErrorCode::InvalidInput,
+                format!("unknown `$operation` {selector}; expected one of: {known}"),
+            )
+        }
+    }
+}
+
+fn handle_message_output(input: &serde_json::Value) -> serde_json::Value {
+    let input_text = input
+        .get("input")
+        .and_then(|v| v.as_str())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| input.to_string());
+
+    serde_json::json!({
+        "message": handle_message("handle_message", &input_text)
+    })
+}
+
+// Pipelines assert that a fetched artifact matches the contract a flow was
+// authored against: `expected` is compared (hex, case-insensitive) with each
+// operation's recomputed `schema_hash`, and the matching operation id is returned.
+pub fn verify_schema_hash(expected: &str) -> Result<&'static str, String> {
+    let actual = operation_schema_hashes()?;
+    let expected = expected.trim();
+    actual
+        .iter()
+        .find(|(_, hash)| hash.eq_ignore_ascii_case(expected))
+        .map(|(id, _)| *id)
+        .ok_or_else(|| {
+            let known = actual
+                .iter()
+                .map(|(id, hash)| format!("{id}={hash}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("schema_hash `{expected}` matches no operation ({known})")
+        })
+}
+
+fn operation_schema_hashes() -> Result<BTreeMap<&'static str, String>, String> {
+    let config = config_schema_ir();
+    operations::OPERATIONS
+        .iter()
+        .map(|operation| Ok((operation.id, operation.schema_hash(&config)?)))
+        .collect()
+}
+
+// `run` form of `verify_schema_hash`: `{ "expected": "...", "target"? }`,
+// where `target` pins the comparison to one operation id.
+pub(crate) fn verify_schema_hash_output(input: &serde_json::Value) -> serde_json::Value {
+    let Some(expected) = input.get("expected").and_then(|v| v.as_str()) else {
+        return codec::error_document(
+            greentic_types::ErrorCode::InvalidInput,
+            "`expected` schema_hash is required",
+        );
+    };
+    let actual = match operation_schema_hashes() {
+        Ok(actual) => actual,
+        Err(err) => return codec::internal_error(err),
+    };
+    let matched = match input.get("target").and_then(|v| v.as_str()) {
+        Some(target) => actual
+            .get(target)
+            .filter(|hash| hash.eq_ignore_ascii_case(expected.trim()))
+            .map(|_| target.to_string()),
+        None => verify_schema_hash(expected).ok().map(str::to_string),
+    };
+    serde_json::json!({
+        "matches": matched.is_some(),
+        "operation": matched,
+        "actual": actual,
+    })
+}
+
+// Byte-level `run`: accepts CBOR, JSON or MessagePack input and answers in
+// the same encoding, so non-CBOR hosts and debug harnesses can call it directly.
+pub fn run_bytes(input: &[u8]) -> Vec<u8> {
+    let (encoding, value) = codec::decode(input);
+    codec::encode_or_error(encoding, &run_output(&value))
+}
+
+// Byte-level `apply_answers`: both arguments may be CBOR, JSON or MessagePack;
+// the result is always a canonical CBOR config, as the WIT export promises.
+// Rejected answers leave the config unchanged; issues are listed under
+// `APPLY_ANSWERS_ISSUES_KEY`.
+pub fn apply_answers_bytes(current_config: &[u8], answers: &[u8]) -> Vec<u8> {
+    let updated = apply_answers_result(current_config, answers).into_document();
+    codec::encode_or_error(codec::WireEncoding::Cbor, &updated)
+}
+
+// Reserved key that carries `{ errors, warnings }` in the document the WIT
+// `apply-answers` export returns. Transport-only: it is not part of the
+// config schema, hosts strip it before storing the config, and it is
+// dropped from the incoming config before answers are applied.
+pub const APPLY_ANSWERS_ISSUES_KEY: &str = "$apply_answers";
+
+// Outcome of `apply_answers` for setup UIs: the config to store plus what
+// went wrong. With any error the config is the unchanged current config.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApplyAnswersResult {
+    pub config: serde_json::Value,
+    pub warnings: Vec<AnswerIssue>,
+    pub errors: Vec<AnswerIssue>,
+}
+
+// `code` is a stable `CFG_*` identifier; `message` is English detail.
+// Failed question rules also carry the catalog key (`msg_key`) and the
+// question id, so setup UIs can localize and highlight the field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnswerIssue {
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msg_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl AnswerIssue {
+    fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            msg_key: None,
+            field: None,
+        }
+    }
+
+    fn invalid_answer(rule: &validation::QuestionRule) -> Self {
+        Self {
+            code: "CFG_INVALID_ANSWER".to_string(),
+            message: i18n::t("en", rule.error_key),
+            msg_key: Some(rule.error_key.to_string()),
+            field: Some(rule.question_id.to_string()),
+        }
+    }
+}
+
+impl ApplyAnswersResult {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    // The WIT shape: the config, with any warnings and errors under
+    // `APPLY_ANSWERS_ISSUES_KEY`. With errors it is the unchanged current
+    // config. A non-object config has nowhere to carry the issues and is
+    // returned as-is.
+    pub fn into_document(self) -> serde_json::Value {
+        if self.warnings.is_empty() && self.errors.is_empty() {
+            return self.config;
+        }
+        let serde_json::Value::Object(mut config) = self.config else {
+            return self.config;
+        };
+        config.insert(
+            APPLY_ANSWERS_ISSUES_KEY.to_string(),
+            serde_json::json!({ "errors": self.errors, "warnings": self.warnings }),
+        );
+        serde_json::Value::Object(config)
+    }
+}
+
+// Decodes both documents and applies the answers, reporting undecodable
+// input, unknown rollback ids and template limit violations.
+pub fn apply_answers_result(current_config: &[u8], answers: &[u8]) -> ApplyAnswersResult {
+    let mut warnings = Vec::new();
+    let current = match codec::try_decode(current_config) {
+        Some((_, current)) => current,
+        None => {
+            if !current_config.is_empty() {
+                warnings.push(AnswerIssue::new(
+                    "CFG_UNDECODABLE_CONFIG",
+                    "current config could not be decoded; starting from an empty config",
+                ));
+            }
+            serde_json::json!({})
+        }
+    };
+    let Some((_, answers)) = codec::try_decode(answers) else {
+        return ApplyAnswersResult {
+            config: current,
+            warnings,
+            errors: vec![AnswerIssue::new(
+                "CFG_UNDECODABLE_ANSWERS",
+                "answers could not be decoded as CBOR, JSON or MessagePack",
+            )],
+        };
+    };
+    let mut result = apply_answers_report(current, answers);
+    warnings.append(&mut result.warnings);
+    result.warnings = warnings;
+    result
+}
+
+// `apply_answers_report(...).into_document()`, for callers holding decoded
+// documents.
+pub fn apply_answers_checked(
+    current_config: serde_json::Value,
+    answers: serde_json::Value,
+) -> serde_json::Value {
+    apply_answers_report(current_config, answers).into_document()
+}
+
+pub fn apply_answers_report(
+    mut current_config: serde_json::Value,
+    answers: serde_json::Value,
+) -> ApplyAnswersResult {
+    // Issues from an earlier call describe that call, not this one.
+    if let Some(map) = current_config.as_object_mut() {
+        map.remove(APPLY_ANSWERS_ISSUES_KEY);
+    }
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    // Every check below reads this one normalized view, so a legacy wrapper
+    // or `Templates:` key cannot hide limits or history from them.
+    let (config, deprecated) = config::normalized_config(&current_config);
+    if let Some(warning) = deprecated {
+        warnings.push(AnswerIssue::new("CFG_DEPRECATED_KEY", warning));
+    }
+
+    if let Some(text) = extract_template_text_answer(&answers) {
+        if let Err(violation) = limits::TemplateLimits::from_config(&config).check(&text) {
+            errors.push(AnswerIssue::new(violation.kind(), violation.message()));
+        } else if let Some(rule) = validation::rule_for("templates.text")
+            && !rule.validate(&text)
+        {
+            errors.push(AnswerIssue::invalid_answer(rule));
+        }
+    }
+    if let Some(id) = extract_rollback_answer(&answers) {
+        let known = config::TemplatesConfig::from_config(&config)
+            .map(|templates| templates.versions.iter().any(|version| version.id == id))
+            .unwrap_or(false);
+        if let Some(rule) = validation::rule_for("rollback")
+            && !rule.validate(&id)
+        {
+            errors.push(AnswerIssue::invalid_answer(rule));
+        } else if !known {
+            errors.push(AnswerIssue::new(
+                "CFG_UNKNOWN_VERSION",
+                format!("rollback names unknown version `{id}`"),
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return ApplyAnswersResult {
+            config: current_config,
+            warnings,
+            errors,
+        };
+    }
+    ApplyAnswersResult {
+        config: apply_template_answers(current_config, answers),
+        warnings,
+        errors,
+    }
+}
+
+// Sample shown by flow editors when the component is dropped on a canvas.
+// The expected output is computed through `run_output`, so it cannot drift.
+pub fn example_invocation() -> serde_json::Value {
+    let input = serde_json::json!({ "input": "Hello from component-templates!" });
+    serde_json::json!({
+        "config": { "templates": { "text": "Hello {{name}}" } },
+        "input": input,
+        "output": run_output(&input)
+    })
+}
+
 fn qa_spec_payload(mode_key: &str) -> ComponentQaSpec {
     let mode = match mode_key {
         "default" => QaModeSpec::Default,
@@ -152,12 +428,12 @@ fn qa_spec_payload(mode_key: &str) -> ComponentQaSpec {
     };
     let asks_template_text = matches!(mode_key, "default" | "setup" | "update");
     let required = matches!(mode_key, "default" | "setup");
-    let questions = if asks_template_text {
+    let mut questions = if asks_template_text {
         vec![Question {
             id: "templates.text".to_string(),
             label: I18nText::new("qa.text.label", None),
             help: None,
-            error: None,
+            error: question_error("templates.text"),
             kind: QuestionKind::Text,
             required,
             default: None,
@@ -165,6 +441,17 @@ fn qa_spec_payload(mode_key: &str) -> ComponentQaSpec {
     } else {
         Vec::new()
     };
+    if mode_key == "update" {
+        questions.push(Question {
+            id: "rollback".to_string(),
+            label: I18nText::new("qa.rollback.label", None),
+            help: Some(I18nText::new("qa.rollback.help", None)),
+            error: question_error("rollback"),
+            kind: QuestionKind::Text,
+            required: false,
+            default: None,
+        });
+    }
 
     ComponentQaSpec {
         mode,
@@ -175,7 +462,73 @@ fn qa_spec_payload(mode_key: &str) -> ComponentQaSpec {
     }
 }
 
-#[cfg(any(target_arch = "wasm32", test))]
+// Update-mode spec tailored to an existing config: the text question is
+// pre-filled with the current text and only required when that text is
+// missing or fails validation, and rollback is only offered when there is
+// history to roll back to. Other modes ignore `current_config`.
+pub fn qa_spec_for_config(mode_key: &str, current_config: &serde_json::Value) -> ComponentQaSpec {
+    let mut spec = qa_spec_payload(mode_key);
+    if mode_key != "update" {
+        return spec;
+    }
+    let (config, _) = config::normalized_config(current_config);
+    let templates = config::TemplatesConfig::from_config(&config).ok();
+    let current_text = templates
+        .as_ref()
+        .map(|templates| templates.text.clone())
+        .filter(|text| !text.trim().is_empty());
+    let text_is_valid = current_text.as_deref().is_some_and(|text| {
+        limits::TemplateLimits::from_config(&config)
+            .check(text)
+            .is_ok()
+            && ast::TemplateAst::parse(text).is_ok()
+    });
+    let has_history = templates.is_some_and(|templates| templates.versions.len() > 1);
+
+    spec.questions
+        .retain(|question| question.id != "rollback" || has_history);
+    for question in &mut spec.questions {
+        if question.id == "templates.text" {
+            question.required = !text_is_valid;
+            question.default = current_text.clone().map(ciborium::value::Value::Text);
+        }
+    }
+    if let Some(text) = current_text {
+        spec.defaults.insert(
+            "templates.text".to_string(),
+            ciborium::value::Value::Text(text),
+        );
+    }
+    spec
+}
+
+// `run` form of `qa_spec_for_config`, for hosts that hold the node's config:
+// `{ "$operation": "qa_spec", "mode": "update", "current_config"? }`. The
+// WIT `qa-spec` export takes only a mode, so this is how a guest sees it.
+pub(crate) fn qa_spec_output(input: &serde_json::Value) -> serde_json::Value {
+    let Some(mode) = input
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .filter(|mode| QA_MODES.contains(mode))
+    else {
+        return codec::error_document(
+            greentic_types::ErrorCode::InvalidInput,
+            format!("`mode` must be one of: {}", QA_MODES.join(", ")),
+        );
+    };
+    let current_config = input
+        .get("current_config")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    serde_json::to_value(qa_spec_for_config(mode, &current_config))
+        .unwrap_or_else(|err| codec::internal_error(err.to_string()))
+}
+
+// Catalog message shown when the question's validation rule fails.
+fn question_error(question_id: &str) -> Option<I18nText> {
+    validation::rule_for(question_id).map(|rule| I18nText::new(rule.error_key, None))
+}
+
 fn extract_template_text_answer(answers: &serde_json::Value) -> Option<String> {
     if let Some(value) = answers.as_str() {
         return Some(value.to_string());
@@ -208,52 +561,102 @@ fn extract_template_text_answer(answers: &serde_json::Value) -> Option<String> {
         .map(ToOwned::to_owned)
 }
 
-#[cfg(any(target_arch = "wasm32", test))]
 fn apply_template_answers(
     current_config: serde_json::Value,
     answers: serde_json::Value,
 ) -> serde_json::Value {
     // Compatibility: older flows may send a wrapped object like
-    // { "component": "...", "config": { ... } }. Unwrap to preserve the
-    // component config contract expected by schema validation.
-    let normalized_current_config = current_config
-        .as_object()
-        .and_then(|map| map.get("config"))
-        .and_then(|value| value.as_object())
-        .map(|map| serde_json::Value::Object(map.clone()))
-        .unwrap_or(current_config);
-
-    let mut config = match normalized_current_config {
+    // { "component": "...", "config": { ... } } or a `Templates:` key.
+    // `apply_answers_report` surfaces the deprecation warning; the stored
+    // config follows the component config contract.
+    let mut config = match config::normalized_config(&current_config).0 {
         serde_json::Value::Object(map) => map,
         _ => serde_json::Map::new(),
     };
 
-    if let Some(text) = extract_template_text_answer(&answers) {
-        let mut templates = match config.remove("templates") {
-            Some(serde_json::Value::Object(map)) => map,
-            _ => serde_json::Map::new(),
-        };
-        templates.insert("text".to_string(), serde_json::Value::String(text));
-        config.insert(
-            "templates".to_string(),
-            serde_json::Value::Object(templates),
-        );
+    let rollback = extract_rollback_answer(&answers);
+    let text = extract_template_text_answer(&answers);
+    if rollback.is_none() && text.is_none() {
+        return serde_json::Value::Object(config);
     }
 
+    let mut templates = match config.remove("templates") {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    // Rollback first, so a combined answer restores and then edits on top.
+    if let Some(id) = rollback {
+        config::rollback_template(&mut templates, &id);
+    }
+    if let Some(text) = text {
+        let created_at = answers.get("created_at").and_then(|v| v.as_u64());
+        config::record_template_version(&mut templates, text, created_at);
+    }
+    config.insert(
+        "templates".to_string(),
+        serde_json::Value::Object(templates),
+    );
+
     serde_json::Value::Object(config)
 }
 
-#[cfg(target_arch = "wasm32")]
-fn encode_cbor<T: serde::Serialize>(value: &T) -> Vec<u8> {
-    canonical::to_canonical_cbor_allow_floats(value).expect("encode cbor")
+// `rollback` names a `templates.versions` id to make active again.
+fn extract_rollback_answer(answers: &serde_json::Value) -> Option<String> {
+    answers
+        .get("rollback")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(ToOwned::to_owned)
 }
 
-#[cfg(target_arch = "wasm32")]
-fn parse_payload(input: &[u8]) -> serde_json::Value {
-    if let Ok(value) = canonical::from_cbor(input) {
-        return value;
+// Describe without `metadata.build`: provenance changes with every commit
+// and is not part of the contract.
+pub fn contract_describe() -> Result<ComponentDescribe, String> {
+    let mut describe = component_describe()?;
+    describe.metadata.remove("build");
+    Ok(describe)
+}
+
+// QA modes exposed through `qa_spec`, keyed as in the WIT `qa-mode` enum.
+pub const QA_MODES: &[&str] = &["default", "setup", "update", "remove"];
+
+// Canonical CBOR of every static contract artifact the component exports:
+// describe, the three schemas and each QA mode. Keys name the artifact
+// (`describe`, `schema.input`, `qa.setup`, ...) so snapshot diffs stay readable.
+pub fn contract_bytes() -> Result<BTreeMap<String, Vec<u8>>, String> {
+    use codec::WireEncoding::Cbor;
+
+    let mut artifacts = BTreeMap::from([
+        (
+            "describe".to_string(),
+            codec::encode(Cbor, &contract_describe()?)?,
+        ),
+        (
+            "schema.input".to_string(),
+            codec::encode(Cbor, &input_schema_ir())?,
+        ),
+        (
+            "schema.output".to_string(),
+            codec::encode(Cbor, &output_schema_ir())?,
+        ),
+        (
+            "schema.config".to_string(),
+            codec::encode(Cbor, &config_schema_ir())?,
+        ),
+    ]);
+    for mode in QA_MODES {
+        artifacts.insert(
+            format!("qa.{mode}"),
+            codec::encode(Cbor, &qa_spec_payload(mode))?,
+        );
     }
-    serde_json::from_slice(input).unwrap_or_else(|_| serde_json::json!({}))
+    Ok(artifacts)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn encode_cbor<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    codec::encode_or_error(codec::WireEncoding::Cbor, value)
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -266,55 +669,43 @@ fn mode_key(mode: QaMode) -> &'static str {
     }
 }
 
-#[cfg(target_arch = "wasm32")]
-fn input_schema() -> SchemaIr {
-    SchemaIr::Object {
-        properties: BTreeMap::from([(
-            "input".to_string(),
-            SchemaIr::String {
-                min_len: Some(0),
-                max_len: None,
-                regex: None,
-                format: None,
-            },
-        )]),
-        required: vec!["input".to_string()],
-        additional: AdditionalProperties::Allow,
-    }
+// Input contract of the default operation (`run`).
+pub fn input_schema_ir() -> SchemaIr {
+    (operations::default_operation().input_schema)()
 }
 
-#[cfg(target_arch = "wasm32")]
-fn output_schema() -> SchemaIr {
-    SchemaIr::Object {
-        properties: BTreeMap::from([(
-            "message".to_string(),
-            SchemaIr::String {
-                min_len: Some(0),
-                max_len: None,
-                regex: None,
-                format: None,
-            },
-        )]),
-        required: vec!["message".to_string()],
-        additional: AdditionalProperties::Allow,
-    }
+// Output contract of the default operation (`run`).
+pub fn output_schema_ir() -> SchemaIr {
+    (operations::default_operation().output_schema)()
 }
 
-#[cfg(target_arch = "wasm32")]
-fn config_schema() -> SchemaIr {
+pub fn config_schema_ir() -> SchemaIr {
     SchemaIr::Object {
         properties: BTreeMap::from([(
             "templates".to_string(),
             SchemaIr::Object {
-                properties: BTreeMap::from([(
-                    "text".to_string(),
-                    SchemaIr::String {
-                        min_len: Some(0),
-                        max_len: None,
-                        regex: None,
-                        format: None,
-                    },
-                )]),
+                properties: BTreeMap::from([
+                    (
+                        "text".to_string(),
+                        SchemaIr::String {
+                            min_len: Some(0),
+                            max_len: None,
+                            regex: None,
+                            format: None,
+                        },
+                    ),
+                    ("versions".to_string(), template_versions_schema()),
+                    (
+                        "active".to_string(),
+                        SchemaIr::String {
+                            min_len: Some(1),
+                            max_len: None,
+                            regex: None,
+                            format: None,
+                        },
+                    ),
+                    ("limits".to_string(), template_limits_schema()),
+                ]),
                 required: vec!["text".to_string()],
                 additional: AdditionalProperties::Allow,
             },
@@ -324,7 +715,52 @@ fn config_schema() -> SchemaIr {
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+// { max_bytes?, max_depth?, max_helpers? }, each capped by the host defaults.
+fn template_limits_schema() -> SchemaIr {
+    let limit = |max: usize| SchemaIr::Int {
+        min: Some(1),
+        max: Some(max as i64),
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("max_bytes".to_string(), limit(limits::MAX_TEMPLATE_BYTES)),
+            ("max_depth".to_string(), limit(limits::MAX_BLOCK_DEPTH)),
+            ("max_helpers".to_string(), limit(limits::MAX_HELPER_CALLS)),
+        ]),
+        required: Vec::new(),
+        additional: AdditionalProperties::Forbid,
+    }
+}
+
+// [{ id, text, created_at? }]
+fn template_versions_schema() -> SchemaIr {
+    let text = |min_len| SchemaIr::String {
+        min_len: Some(min_len),
+        max_len: None,
+        regex: None,
+        format: None,
+    };
+    SchemaIr::Array {
+        items: Box::new(SchemaIr::Object {
+            properties: BTreeMap::from([
+                ("id".to_string(), text(1)),
+                ("text".to_string(), text(0)),
+                (
+                    "created_at".to_string(),
+                    SchemaIr::Int {
+                        min: Some(0),
+                        max: None,
+                    },
+                ),
+            ]),
+            required: vec!["id".to_string(), "text".to_string()],
+            additional: AdditionalProperties::Forbid,
+        }),
+        min_items: None,
+        max_items: Some(config::MAX_TEMPLATE_VERSIONS as u64),
+    }
+}
+
 fn component_info() -> ComponentInfo {
     ComponentInfo {
         id: format!("{COMPONENT_ORG}.{COMPONENT_NAME}"),
@@ -337,30 +773,115 @@ fn component_info() -> ComponentInfo {
     }
 }
 
-#[cfg(target_arch = "wasm32")]
-fn component_describe() -> ComponentDescribe {
-    let input = input_schema();
-    let output = output_schema();
-    let config = config_schema();
-    let op_schema_hash = schema_hash(&input, &output, &config).unwrap_or_default();
+fn component_describe() -> Result<ComponentDescribe, String> {
+    let config = config_schema_ir();
 
-    ComponentDescribe {
+    Ok(ComponentDescribe {
         info: component_info(),
         provided_capabilities: Vec::new(),
         required_capabilities: Vec::new(),
-        metadata: BTreeMap::new(),
-        operations: vec![ComponentOperation {
-            id: "handle_message".to_string(),
-            display_name: Some(I18nText::new("component.operation.handle_message", None)),
-            input: ComponentRunInput { schema: input },
-            output: ComponentRunOutput { schema: output },
-            defaults: BTreeMap::new(),
-            redactions: Vec::new(),
-            constraints: BTreeMap::new(),
-            schema_hash: op_schema_hash,
-        }],
+        metadata: describe_metadata()?,
+        operations: operations::OPERATIONS
+            .iter()
+            .map(|operation| operation.to_component_operation(&config))
+            .collect::<Result<_, _>>()?,
         config_schema: config,
+    })
+}
+
+// Config properties with localized help; keys are `config.<path>.title`
+// and `config.<path>.description` in assets/i18n.
+const CONFIG_FIELDS: &[&str] = &[
+    "templates.text",
+    "templates.versions",
+    "templates.active",
+    "templates.limits",
+];
+
+// SchemaIr has no title/description slots, so setup UIs read field help
+// from metadata: { "<path>": { "title": I18nText, "description": I18nText } }.
+fn config_i18n_metadata() -> Result<ciborium::value::Value, String> {
+    use ciborium::value::Value;
+
+    let text = |key: String| {
+        let fallback = i18n::t("en", &key);
+        Value::serialized(&I18nText::new(key.clone(), Some(fallback)))
+            .map_err(|err| format!("config_i18n {key}: {err}"))
+    };
+    let entries = CONFIG_FIELDS
+        .iter()
+        .map(|path| {
+            let entry = Value::Map(vec![
+                (
+                    Value::Text("title".to_string()),
+                    text(format!("config.{path}.title"))?,
+                ),
+                (
+                    Value::Text("description".to_string()),
+                    text(format!("config.{path}.description"))?,
+                ),
+            ]);
+            Ok((Value::Text((*path).to_string()), entry))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Value::Map(entries))
+}
+
+// Provenance of this build; either field may be absent (see build.rs).
+pub fn build_metadata() -> serde_json::Value {
+    let mut build = serde_json::Map::new();
+    if let Some(sha) = option_env!("COMPONENT_BUILD_GIT_SHA") {
+        build.insert("git_sha".to_string(), sha.into());
+    }
+    if let Some(epoch) =
+        option_env!("COMPONENT_BUILD_SOURCE_DATE_EPOCH").and_then(|epoch| epoch.parse::<u64>().ok())
+    {
+        build.insert("source_date_epoch".to_string(), epoch.into());
     }
+    serde_json::Value::Object(build)
+}
+
+// Host-facing facts that are not part of the operation contract.
+// Studios read category/tags/icon/documentation to place the palette entry.
+fn describe_metadata() -> Result<BTreeMap<String, ciborium::value::Value>, String> {
+    use ciborium::value::Value;
+
+    let example = Value::serialized(&example_invocation())
+        .map_err(|err| format!("describe example: {err}"))?;
+    let build =
+        Value::serialized(&build_metadata()).map_err(|err| format!("describe build: {err}"))?;
+    Ok(BTreeMap::from([
+        (
+            "category".to_string(),
+            Value::Text(COMPONENT_CATEGORY.to_string()),
+        ),
+        (
+            "tags".to_string(),
+            Value::Array(
+                COMPONENT_TAGS
+                    .iter()
+                    .map(|tag| Value::Text((*tag).to_string()))
+                    .collect(),
+            ),
+        ),
+        (
+            "documentation".to_string(),
+            Value::Text(COMPONENT_DOCS_URL.to_string()),
+        ),
+        ("icon".to_string(), Value::Text(COMPONENT_ICON.to_string())),
+        (
+            "i18n_bundle_hash".to_string(),
+            Value::Text(i18n::bundle_hash().to_string()),
+        ),
+        ("config_i18n".to_string(), config_i18n_metadata()?),
+        ("examples".to_string(), Value::Array(vec![example])),
+        (
+            "qa_validation".to_string(),
+            Value::serialized(validation::QUESTION_RULES)
+                .map_err(|err| format!("describe qa_validation: {err}"))?,
+        ),
+        ("build".to_string(), build),
+    ]))
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -370,22 +891,25 @@ fn component_info_cbor() -> Vec<u8> {
 
 #[cfg(target_arch = "wasm32")]
 fn component_describe_cbor() -> Vec<u8> {
-    encode_cbor(&component_describe())
+    match component_describe() {
+        Ok(describe) => encode_cbor(&describe),
+        Err(message) => encode_cbor(&codec::internal_error(message)),
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
 fn input_schema_cbor() -> Vec<u8> {
-    encode_cbor(&input_schema())
+    encode_cbor(&input_schema_ir())
 }
 
 #[cfg(target_arch = "wasm32")]
 fn output_schema_cbor() -> Vec<u8> {
-    encode_cbor(&output_schema())
+    encode_cbor(&output_schema_ir())
 }
 
 #[cfg(target_arch = "wasm32")]
 fn config_schema_cbor() -> Vec<u8> {
-    encode_cbor(&config_schema())
+    encode_cbor(&config_schema_ir())
 }
 
 #[cfg(test)]
@@ -407,6 +931,103 @@ mod tests {
         assert!(body.contains("demo"));
     }
 
+    #[test]
+    fn describe_metadata_exposes_i18n_bundle_hash() {
+        let describe = component_describe().expect("describe");
+        let hash = describe
+            .metadata
+            .get("i18n_bundle_hash")
+            .and_then(|value| value.as_text())
+            .expect("i18n_bundle_hash text");
+
+        assert_eq!(hash, i18n::bundle_hash());
+        assert!(hash.starts_with("blake3:"));
+        assert_eq!(hash.len(), "blake3:".len() + 64);
+    }
+
+    #[test]
+    fn describe_metadata_carries_palette_hints() {
+        let metadata = component_describe().expect("describe").metadata;
+        assert_eq!(metadata["category"].as_text(), Some("formatting"));
+        assert_eq!(metadata["icon"].as_text(), Some("template"));
+        assert_eq!(
+            metadata["documentation"].as_text(),
+            Some(env!("CARGO_PKG_REPOSITORY"))
+        );
+        let tags: Vec<_> = metadata["tags"]
+            .as_array()
+            .expect("tags array")
+            .iter()
+            .filter_map(|tag| tag.as_text())
+            .collect();
+        assert_eq!(tags, ["template", "text"]);
+    }
+
+    #[test]
+    fn describe_advertises_only_config_the_runtime_reads() {
+        let describe = component_describe().expect("describe");
+        assert!(describe.operations[0].defaults.is_empty());
+
+        let fields = config_i18n_metadata().expect("config metadata");
+        let paths: Vec<_> = fields
+            .as_map()
+            .expect("metadata map")
+            .iter()
+            .filter_map(|(path, _)| path.as_text())
+            .collect();
+        for unread in [
+            "templates.output_path",
+            "templates.wrap",
+            "templates.routing",
+        ] {
+            assert!(!paths.contains(&unread), "{unread} is advertised");
+        }
+    }
+
+    #[test]
+    fn describe_carries_build_metadata_outside_the_contract() {
+        let metadata = component_describe().expect("describe").metadata;
+        let build: serde_json::Value = metadata["build"].deserialized().expect("build decodes");
+        assert_eq!(build, build_metadata());
+        if let Some(sha) = build.get("git_sha") {
+            assert!(sha.as_str().is_some_and(|sha| !sha.is_empty()));
+        }
+
+        let contract = contract_describe().expect("contract describe");
+        assert!(!contract.metadata.contains_key("build"));
+        assert_eq!(contract.metadata.len(), metadata.len() - 1);
+    }
+
+    #[test]
+    fn describe_examples_match_run_output() {
+        let metadata = component_describe().expect("describe").metadata;
+        let examples: Vec<serde_json::Value> = metadata["examples"]
+            .deserialized()
+            .expect("examples decode as json");
+        let example = examples.first().expect("one example");
+
+        assert_eq!(example, &example_invocation());
+        assert_eq!(example["output"], run_output(&example["input"]));
+        assert!(example["config"]["templates"]["text"].is_string());
+    }
+
+    #[test]
+    fn describe_config_i18n_keys_exist_in_en_catalog() {
+        let metadata = component_describe().expect("describe").metadata;
+        let fields: BTreeMap<String, BTreeMap<String, I18nText>> = metadata["config_i18n"]
+            .deserialized()
+            .expect("config_i18n decodes");
+        let en = i18n::en_messages();
+
+        assert_eq!(fields.len(), CONFIG_FIELDS.len());
+        for (path, texts) in fields {
+            for text in texts.values() {
+                assert!(en.contains_key(&text.key), "{path}: missing {}", text.key);
+                assert_eq!(text.fallback.as_deref(), Some(en[&text.key].as_str()));
+            }
+        }
+    }
+
     #[test]
     fn qa_spec_default_includes_text_question() {
         let spec = qa_spec_payload("default");
@@ -467,6 +1088,292 @@ mod tests {
         assert_eq!(updated, current);
     }
 
+    #[test]
+    fn apply_answers_keeps_version_history_and_rolls_back() {
+        let current = serde_json::json!({ "templates": { "text": "First" } });
+        let edited = apply_template_answers(
+            current,
+            serde_json::json!({ "text": "Second", "created_at": 1_700_000_000u64 }),
+        );
+        assert_eq!(edited["templates"]["text"], "Second");
+        assert_eq!(edited["templates"]["active"], "v2");
+        assert_eq!(edited["templates"]["versions"][0]["text"], "First");
+        assert_eq!(
+            edited["templates"]["versions"][1]["created_at"],
+            1_700_000_000u64
+        );
+
+        let restored = apply_template_answers(edited, serde_json::json!({ "rollback": "v1" }));
+        assert_eq!(restored["templates"]["text"], "First");
+        assert_eq!(restored["templates"]["active"], "v1");
+        assert_eq!(
+            restored["templates"]["versions"].as_array().map(Vec::len),
+            Some(2)
+        );
+        config::TemplatesConfig::from_config(&restored).expect("history parses");
+    }
+
+    #[test]
+    fn apply_answers_renames_miscapitalized_templates_key() {
+        let current = serde_json::json!({ "Templates": { "text": "Old", "wrap": false } });
+        let updated = apply_template_answers(current, serde_json::json!({}));
+        assert_eq!(
+            updated,
+            serde_json::json!({ "templates": { "text": "Old", "wrap": false } })
+        );
+    }
+
+    #[test]
+    fn verify_schema_hash_matches_published_operation_hashes() {
+        let describe = component_describe().expect("describe");
+        for operation in &describe.operations {
+            assert_eq!(
+                verify_schema_hash(&operation.schema_hash.to_uppercase()),
+                Ok(operation.id.as_str())
+            );
+        }
+        assert!(verify_schema_hash("deadbeef").is_err());
+
+        let expected = &describe.operations[0].schema_hash;
+        let pinned = verify_schema_hash_output(&serde_json::json!({
+            "expected": expected,
+            "target": operations::DIAGNOSTICS_OPERATION,
+        }));
+        assert_eq!(pinned["matches"], false);
+        assert_eq!(pinned["actual"][&describe.operations[0].id], *expected);
+        let missing = verify_schema_hash_output(&serde_json::json!({}));
+        assert_eq!(missing["error"]["code"], "invalid_input");
+    }
+
+    #[test]
+    fn apply_answers_result_reports_warnings_and_errors() {
+        let current = codec::encode(
+            codec::WireEncoding::Cbor,
+            &serde_json::json!({ "Templates": { "text": "Old" } }),
+        )
+        .unwrap();
+        let answers = serde_json::to_vec(&serde_json::json!({ "text": "New" })).unwrap();
+        let result = apply_answers_result(&current, &answers);
+        assert!(result.is_ok());
+        assert_eq!(result.config["templates"]["text"], "New");
+        assert_eq!(result.warnings[0].code, "CFG_DEPRECATED_KEY");
+
+        let result = apply_answers_result(&[0xff, 0x00], &answers);
+        assert_eq!(result.warnings[0].code, "CFG_UNDECODABLE_CONFIG");
+        assert_eq!(result.config["templates"]["text"], "New");
+
+        let result = apply_answers_result(&current, &[0xff, 0x00]);
+        assert_eq!(result.errors[0].code, "CFG_UNDECODABLE_ANSWERS");
+        let document = result.into_document();
+        assert_eq!(document["Templates"]["text"], "Old");
+        assert_eq!(
+            document[APPLY_ANSWERS_ISSUES_KEY]["errors"][0]["code"],
+            "CFG_UNDECODABLE_ANSWERS"
+        );
+
+        let rollback = serde_json::to_vec(&serde_json::json!({ "rollback": "v7" })).unwrap();
+        let result = apply_answers_result(&current, &rollback);
+        assert_eq!(result.errors[0].code, "CFG_UNKNOWN_VERSION");
+        assert_eq!(result.config["Templates"]["text"], "Old");
+    }
+
+    #[test]
+    fn apply_answers_enforces_question_rules_with_catalog_messages() {
+        let current = serde_json::json!({ "templates": { "text": "Old" } });
+        let result = apply_answers_report(
+            current.clone(),
+            serde_json::json!({ "text": "{{#each items}}", "rollback": "latest" }),
+        );
+        let fields: Vec<_> = result
+            .errors
+            .iter()
+            .map(|issue| (issue.code.as_str(), issue.field.as_deref()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("CFG_INVALID_ANSWER", Some("templates.text")),
+                ("CFG_INVALID_ANSWER", Some("rollback")),
+            ]
+        );
+        assert_eq!(result.errors[0].msg_key.as_deref(), Some("qa.text.error"));
+        assert_eq!(result.errors[0].message, i18n::t("en", "qa.text.error"));
+        assert_eq!(result.config, current);
+
+        let spec = qa_spec_payload("update");
+        let errors: Vec<_> = spec
+            .questions
+            .iter()
+            .map(|question| question.error.as_ref().map(|error| error.key.as_str()))
+            .collect();
+        assert_eq!(errors, [Some("qa.text.error"), Some("qa.rollback.error")]);
+    }
+
+    #[test]
+    fn apply_answers_checked_rejects_over_limit_text() {
+        let current = serde_json::json!({
+            "templates": { "text": "Old", "limits": { "max_bytes": 8 } }
+        });
+        let rejected = apply_answers_checked(
+            current.clone(),
+            serde_json::json!({ "text": "far too long for eight bytes" }),
+        );
+        assert_eq!(rejected["templates"], current["templates"]);
+        assert_eq!(
+            rejected[APPLY_ANSWERS_ISSUES_KEY]["errors"][0]["code"],
+            "CFG_TEMPLATE_TOO_LARGE"
+        );
+
+        let accepted = apply_answers_checked(rejected, serde_json::json!({ "text": "Short" }));
+        assert_eq!(accepted["templates"]["text"], "Short");
+        assert!(accepted.get(APPLY_ANSWERS_ISSUES_KEY).is_none());
+    }
+
+    #[test]
+    fn apply_answers_keeps_warnings_on_success() {
+        let updated = apply_answers_checked(
+            serde_json::json!({ "Templates": { "text": "Old" } }),
+            serde_json::json!({ "text": "New" }),
+        );
+        assert_eq!(updated["templates"]["text"], "New");
+        assert_eq!(
+            updated[APPLY_ANSWERS_ISSUES_KEY],
+            serde_json::json!({
+                "errors": [],
+                "warnings": [{
+                    "code": "CFG_DEPRECATED_KEY",
+                    "message": "config key `Templates` is deprecated; use `templates`"
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn qa_spec_operation_reads_the_current_config() {
+        let spec = run_output(&serde_json::json!({
+            "$operation": "qa_spec",
+            "mode": "update",
+            "current_config": { "templates": { "text": "Hi {{name}}" } }
+        }));
+        assert_eq!(
+            spec,
+            serde_json::to_value(qa_spec_for_config(
+                "update",
+                &serde_json::json!({ "templates": { "text": "Hi {{name}}" } })
+            ))
+            .unwrap()
+        );
+        assert_eq!(spec["questions"][0]["default"], "Hi {{name}}");
+        assert_eq!(spec["questions"][0]["required"], false);
+
+        let setup = run_output(&serde_json::json!({ "$operation": "qa_spec", "mode": "setup" }));
+        assert_eq!(
+            setup,
+            serde_json::to_value(qa_spec_payload("setup")).unwrap()
+        );
+
+        let missing = run_output(&serde_json::json!({ "$operation": "qa_spec", "mode": "edit" }));
+        assert_eq!(missing["error"]["code"], "invalid_input");
+    }
+
+    #[test]
+    fn apply_answers_issues_stay_out_of_the_config_contract() {
+        let SchemaIr::Object { properties, .. } = config_schema_ir() else {
+            panic!("config schema is an object");
+        };
+        assert!(!properties.contains_key(APPLY_ANSWERS_ISSUES_KEY));
+
+        let rejected = apply_answers_report(
+            serde_json::json!("not a config"),
+            serde_json::json!({ "rollback": "v7" }),
+        );
+        assert_eq!(rejected.errors[0].code, "CFG_UNKNOWN_VERSION");
+        assert_eq!(rejected.into_document(), "not a config");
+    }
+
+    #[test]
+    fn apply_answers_checks_run_against_the_normalized_config() {
+        let legacy = serde_json::json!({
+            "config": { "Templates": { "text": "a", "limits": { "max_bytes": 4 } } }
+        });
+        let report = apply_answers_report(
+            legacy.clone(),
+            serde_json::json!({ "text": "far too long" }),
+        );
+        let codes: Vec<_> = report
+            .errors
+            .iter()
+            .map(|issue| issue.code.as_str())
+            .collect();
+        assert_eq!(codes, ["CFG_TEMPLATE_TOO_LARGE"]);
+        assert_eq!(report.warnings[0].code, "CFG_DEPRECATED_KEY");
+
+        let spec = qa_spec_for_config(
+            "update",
+            &serde_json::json!({ "Templates": { "text": "too long", "limits": { "max_bytes": 4 } } }),
+        );
+        assert!(spec.questions[0].required);
+        assert_eq!(
+            spec.questions[0].default.as_ref().and_then(|v| v.as_text()),
+            Some("too long")
+        );
+    }
+
+    #[test]
+    fn qa_spec_update_offers_rollback() {
+        let spec = qa_spec_payload("update");
+        let rollback = spec
+            .questions
+            .iter()
+            .find(|question| question.id == "rollback")
+            .expect("rollback question");
+        assert!(!rollback.required);
+        assert!(
+            qa_spec_payload("setup")
+                .questions
+                .iter()
+                .all(|question| question.id != "rollback")
+        );
+    }
+
+    #[test]
+    fn qa_spec_for_config_prefills_update_from_current_config() {
+        let current = apply_template_answers(
+            serde_json::json!({ "templates": { "text": "First" } }),
+            serde_json::json!({ "text": "Second" }),
+        );
+        let spec = qa_spec_for_config("update", &current);
+        let text = &spec.questions[0];
+        assert!(!text.required);
+        assert_eq!(
+            text.default.as_ref().and_then(|v| v.as_text()),
+            Some("Second")
+        );
+        assert_eq!(spec.defaults["templates.text"].as_text(), Some("Second"));
+        assert!(
+            spec.questions
+                .iter()
+                .any(|question| question.id == "rollback")
+        );
+
+        let broken = serde_json::json!({ "templates": { "text": "{{#if a}}" } });
+        let spec = qa_spec_for_config("update", &broken);
+        assert!(spec.questions[0].required);
+        assert!(
+            spec.questions
+                .iter()
+                .all(|question| question.id != "rollback")
+        );
+
+        let spec = qa_spec_for_config("update", &serde_json::json!({}));
+        assert!(spec.questions[0].required);
+        assert!(spec.questions[0].default.is_none());
+        assert_eq!(
+            qa_spec_for_config("setup", &current),
+            qa_spec_payload("setup")
+        );
+    }
+
     #[test]
     fn apply_answers_unwraps_legacy_wrapped_component_config_shape() {
         let current = serde_json::json!({
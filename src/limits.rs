@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use greentic_types::ErrorCode;
+
+use crate::ast::{Tag, Token, classify, tokenize};
+
+// Host-wide ceilings for template text accepted by `apply_answers`.
+pub const MAX_TEMPLATE_BYTES: usize = 64 * 1024;
+pub const MAX_BLOCK_DEPTH: usize = 16;
+pub const MAX_HELPER_CALLS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateLimits {
+    pub max_bytes: usize,
+    pub max_depth: usize,
+    pub max_helpers: usize,
+}
+
+impl Default for TemplateLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: MAX_TEMPLATE_BYTES,
+            max_depth: MAX_BLOCK_DEPTH,
+            max_helpers: MAX_HELPER_CALLS,
+        }
+    }
+}
+
+impl TemplateLimits {
+    // `templates.limits` in a config may tighten the defaults, never raise
+    // them, so a flow author cannot opt out of the host's bounds.
+    pub fn from_config(config: &JsonValue) -> Self {
+        let defaults = Self::default();
+        let Some(requested) = config
+            .get("templates")
+            .and_then(|templates| templates.get("limits"))
+            .and_then(|limits| serde_json::from_value::<Self>(limits.clone()).ok())
+        else {
+            return defaults;
+        };
+        Self {
+            max_bytes: requested.max_bytes.min(defaults.max_bytes),
+            max_depth: requested.max_depth.min(defaults.max_depth),
+            max_helpers: requested.max_helpers.min(defaults.max_helpers),
+        }
+    }
+
+    // A zero limit would reject every template (or every block/helper), so
+    // it is reported as a configuration error rather than applied.
+    pub fn check(&self, text: &str) -> Result<(), LimitViolation> {
+        for (field, limit) in [
+            ("max_bytes", self.max_bytes),
+            ("max_depth", self.max_depth),
+            ("max_helpers", self.max_helpers),
+        ] {
+            if limit == 0 {
+                return Err(LimitViolation::ZeroLimit { field });
+            }
+        }
+        if text.len() > self.max_bytes {
+            return Err(LimitViolation::TooLarge {
+                bytes: text.len(),
+                limit: self.max_bytes,
+            });
+        }
+        let stats = TemplateStats::scan(text);
+        if stats.max_depth > self.max_depth {
+            return Err(LimitViolation::TooDeep {
+                depth: stats.max_depth,
+                limit: self.max_depth,
+            });
+        }
+        if stats.helper_calls > self.max_helpers {
+            return Err(LimitViolation::TooManyHelpers {
+                helpers: stats.helper_calls,
+                limit: self.max_helpers,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LimitViolation {
+    TooLarge { bytes: usize, limit: usize },
+    TooDeep { depth: usize, limit: usize },
+    TooManyHelpers { helpers: usize, limit: usize },
+    ZeroLimit { field: &'static str },
+}
+
+impl LimitViolation {
+    // Stable configuration-class codes for pipelines and setup UIs.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "CFG_TEMPLATE_TOO_LARGE",
+            Self::TooDeep { .. } => "CFG_TEMPLATE_TOO_DEEP",
+            Self::TooManyHelpers { .. } => "CFG_TEMPLATE_TOO_MANY_HELPERS",
+            Self::ZeroLimit { .. } => "CFG_TEMPLATE_LIMIT_ZERO",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::TooLarge { bytes, limit } => {
+                format!("template is {bytes} bytes; the limit is {limit}")
+            }
+            Self::TooDeep { depth, limit } => {
+                format!("template nests blocks {depth} deep; the limit is {limit}")
+            }
+            Self::TooManyHelpers { helpers, limit } => {
+                format!("template calls {helpers} helpers; the limit is {limit}")
+            }
+            Self::ZeroLimit { field } => {
+                format!("templates.limits.{field} must be at least 1")
+            }
+        }
+    }
+
+    // `{ "error": { "code": "invalid_input", "kind": "CFG_...", "message": ... } }`
+    pub fn to_error_document(&self) -> JsonValue {
+        let mut document = crate::codec::error_document(ErrorCode::InvalidInput, self.message());
+        document["error"]["kind"] = JsonValue::String(self.kind().to_string());
+        document
+    }
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.message())
+    }
+}
+
+impl std::error::Error for LimitViolation {}
+
+// Syntactic scan of `{{ ... }}` tags with the outline tokenizer; no engine
+// is involved, so this is an upper bound on what a renderer would do with
+// the text. Scanning stops at the first unterminated tag.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateStats {
+    pub max_depth: usize,
+    pub helper_calls: usize,
+}
+
+impl TemplateStats {
+    pub fn scan(text: &str) -> Self {
+        let mut stats = Self::default();
+        let mut depth = 0usize;
+        for token in tokenize(text) {
+            let body = match token {
+                Ok(Token::Tag { body, .. }) => body,
+                // A raw block is one helper call one level down.
+                Ok(Token::Raw { .. }) => {
+                    stats.helper_calls += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    continue;
+                }
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            let tag = classify(body);
+            stats.helper_calls += tag.helper_calls();
+            match tag {
+                Tag::Open { .. } => {
+                    depth += 1;
+                    stats.max_depth = stats.max_depth.max(depth);
+                }
+                Tag::Close(_) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_counts_blocks_and_helper_calls() {
+        let stats = TemplateStats::scan(
+            "{{!-- note --}}{{#if a}}{{#each b}}{{upper this}}{{/each}}{{/if}} {{name}}",
+        );
+        assert_eq!(
+            stats,
+            TemplateStats {
+                max_depth: 2,
+                helper_calls: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn scan_counts_subexpressions_chained_else_and_inverted_blocks() {
+        let stats = TemplateStats::scan(
+            "{{#if (eq a (lower b))}}x{{else if c}}y{{^}}z{{/if}}{{^items}}none{{/items}}",
+        );
+        assert_eq!(
+            stats,
+            TemplateStats {
+                max_depth: 1,
+                helper_calls: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn scan_ignores_braces_inside_comments_and_literals() {
+        let stats = TemplateStats::scan(
+            "{{!-- {{#if a}} }} --}}{{upper \"}}\"}}{{#each list as |item|}}{{item}}{{/each}}",
+        );
+        assert_eq!(
+            stats,
+            TemplateStats {
+                max_depth: 1,
+                helper_calls: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn scan_counts_raw_blocks_but_not_partials_or_decorators() {
+        let stats = TemplateStats::scan(
+            "{{#*inline \"a\"}}{{#> layout}}{{/layout}}{{/inline}}{{{{raw}}}}{{#if x}}{{{{/raw}}}}",
+        );
+        assert_eq!(
+            stats,
+            TemplateStats {
+                max_depth: 2,
+                helper_calls: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn check_reports_the_first_exceeded_limit() {
+        let limits = TemplateLimits {
+            max_bytes: 64,
+            max_depth: 1,
+            max_helpers: 1,
+        };
+        assert_eq!(limits.check("Hello {{name}}"), Ok(()));
+        let violation = limits.check(&"x".repeat(65)).unwrap_err();
+        assert!(matches!(
+            violation,
+            LimitViolation::TooLarge {
+                bytes: 65,
+                limit: 64
+            }
+        ));
+        assert_eq!(
+            violation.to_string(),
+            "CFG_TEMPLATE_TOO_LARGE: template is 65 bytes; the limit is 64"
+        );
+        assert_eq!(
+            limits.check(&"x".repeat(65)).unwrap_err().kind(),
+            "CFG_TEMPLATE_TOO_LARGE"
+        );
+        assert_eq!(
+            limits
+                .check("{{#if a}}{{#if b}}{{/if}}{{/if}}")
+                .unwrap_err()
+                .kind(),
+            "CFG_TEMPLATE_TOO_DEEP"
+        );
+        assert_eq!(
+            limits.check("{{upper a}}{{lower b}}").unwrap_err().kind(),
+            "CFG_TEMPLATE_TOO_MANY_HELPERS"
+        );
+    }
+
+    #[test]
+    fn config_limits_only_tighten_defaults() {
+        let config = serde_json::json!({
+            "templates": { "limits": { "max_bytes": 10, "max_depth": 1000 } }
+        });
+        let limits = TemplateLimits::from_config(&config);
+        assert_eq!(limits.max_bytes, 10);
+        assert_eq!(limits.max_depth, MAX_BLOCK_DEPTH);
+        assert_eq!(limits.max_helpers, MAX_HELPER_CALLS);
+    }
+
+    #[test]
+    fn zero_limits_are_rejected() {
+        let config = serde_json::json!({ "templates": { "limits": { "max_depth": 0 } } });
+        let violation = TemplateLimits::from_config(&config)
+            .check("plain text")
+            .unwrap_err();
+        assert_eq!(violation.kind(), "CFG_TEMPLATE_LIMIT_ZERO");
+        assert_eq!(
+            violation.message(),
+            "templates.limits.max_depth must be at least 1"
+        );
+    }
+}
@@ -0,0 +1,514 @@
+use std::collections::BTreeMap;
+
+use ciborium::value::Value as CborValue;
+use greentic_types::i18n_text::I18nText;
+use greentic_types::schemas::common::schema_ir::{AdditionalProperties, SchemaIr};
+use greentic_types::schemas::component::v0_6_0::{
+    ComponentOperation, ComponentRunInput, ComponentRunOutput, schema_hash,
+};
+use serde_json::Value as JsonValue;
+
+// Operation served by `run` and the component-schema export.
+pub const DEFAULT_OPERATION: &str = "handle_message";
+pub const DIAGNOSTICS_OPERATION: &str = "diagnostics";
+pub const VERIFY_SCHEMA_HASH_OPERATION: &str = "verify_schema_hash";
+pub const AST_OPERATION: &str = "ast";
+pub const SCHEMAS_OPERATION: &str = "schemas";
+pub const QA_SPEC_OPERATION: &str = "qa_spec";
+
+// Reserved `run` input field naming the operation to dispatch to. The `$`
+// keeps it out of the way of message fields; an unknown name is rejected
+// rather than run as a message.
+pub const OPERATION_SELECTOR: &str = "$operation";
+
+// Registry entry for one exported operation. Each operation owns its
+// input/output contract; the config schema is shared by the component.
+#[derive(Debug, Clone, Copy)]
+pub struct OperationSpec {
+    pub id: &'static str,
+    pub display_name_key: &'static str,
+    pub input_schema: fn() -> SchemaIr,
+    pub output_schema: fn() -> SchemaIr,
+    pub defaults: fn() -> BTreeMap<String, CborValue>,
+    pub constraints: fn() -> BTreeMap<String, CborValue>,
+    pub run: fn(&JsonValue) -> JsonValue,
+}
+
+impl OperationSpec {
+    // Hash of (input, output, config) as published in describe.
+    pub fn schema_hash(&self, config: &SchemaIr) -> Result<String, String> {
+        schema_hash(&(self.input_schema)(), &(self.output_schema)(), config)
+            .map_err(|err| format!("schema_hash({}): {err}", self.id))
+    }
+
+    pub fn to_component_operation(&self, config: &SchemaIr) -> Result<ComponentOperation, String> {
+        let schema_hash = self.schema_hash(config)?;
+
+        Ok(ComponentOperation {
+            id: self.id.to_string(),
+            display_name: Some(I18nText::new(self.display_name_key, None)),
+            input: ComponentRunInput {
+                schema: (self.input_schema)(),
+            },
+            output: ComponentRunOutput {
+                schema: (self.output_schema)(),
+            },
+            defaults: (self.defaults)(),
+            redactions: Vec::new(),
+            constraints: (self.constraints)(),
+            schema_hash,
+        })
+    }
+}
+
+// Register new operations here; describe, the schema exports and `run`
+// dispatch pick them up.
+pub const OPERATIONS: &[OperationSpec] = &[
+    OperationSpec {
+        id: DEFAULT_OPERATION,
+        display_name_key: "component.operation.handle_message",
+        input_schema: handle_message_input_schema,
+        output_schema: handle_message_output_schema,
+        defaults: BTreeMap::new,
+        constraints: template_constraints,
+        run: crate::handle_message_output,
+    },
+    OperationSpec {
+        id: DIAGNOSTICS_OPERATION,
+        display_name_key: "component.operation.diagnostics",
+        input_schema: diagnostics_input_schema,
+        output_schema: diagnostics_output_schema,
+        defaults: BTreeMap::new,
+        constraints: BTreeMap::new,
+        run: crate::diagnostics::diagnostics_output,
+    },
+    OperationSpec {
+        id: VERIFY_SCHEMA_HASH_OPERATION,
+        display_name_key: "component.operation.verify_schema_hash",
+        input_schema: verify_schema_hash_input_schema,
+        output_schema: verify_schema_hash_output_schema,
+        defaults: BTreeMap::new,
+        constraints: BTreeMap::new,
+        run: crate::verify_schema_hash_output,
+    },
+    OperationSpec {
+        id: AST_OPERATION,
+        display_name_key: "component.operation.ast",
+        input_schema: ast_input_schema,
+        output_schema: ast_output_schema,
+        defaults: BTreeMap::new,
+        constraints: BTreeMap::new,
+        run: crate::ast::ast_output,
+    },
+    OperationSpec {
+        id: SCHEMAS_OPERATION,
+        display_name_key: "component.operation.schemas",
+        input_schema: schemas_input_schema,
+        output_schema: schemas_output_schema,
+        defaults: BTreeMap::new,
+        constraints: BTreeMap::new,
+        run: crate::json_schema::schemas_output,
+    },
+    OperationSpec {
+        id: QA_SPEC_OPERATION,
+        display_name_key: "component.operation.qa_spec",
+        input_schema: qa_spec_input_schema,
+        output_schema: qa_spec_output_schema,
+        defaults: BTreeMap::new,
+        constraints: BTreeMap::new,
+        run: crate::qa_spec_output,
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static OperationSpec> {
+    OPERATIONS.iter().find(|operation| operation.id == id)
+}
+
+pub fn default_operation() -> &'static OperationSpec {
+    find(DEFAULT_OPERATION).unwrap_or(&OPERATIONS[0])
+}
+
+// `$operation` of another registered operation, required by that
+// operation's input schema.
+fn selector(id: &str) -> (String, SchemaIr) {
+    (
+        OPERATION_SELECTOR.to_string(),
+        SchemaIr::Enum {
+            values: vec![CborValue::Text(id.to_string())],
+        },
+    )
+}
+
+// The selector is optional here: input without one is a message.
+fn handle_message_input_schema() -> SchemaIr {
+    let text = || SchemaIr::String {
+        min_len: Some(0),
+        max_len: None,
+        regex: None,
+        format: None,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([("input".to_string(), text()), selector(DEFAULT_OPERATION)]),
+        required: vec!["input".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn handle_message_output_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([(
+            "message".to_string(),
+            SchemaIr::String {
+                min_len: Some(0),
+                max_len: None,
+                regex: None,
+                format: None,
+            },
+        )]),
+        required: vec!["message".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn diagnostics_input_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([selector(DIAGNOSTICS_OPERATION)]),
+        required: vec![OPERATION_SELECTOR.to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn diagnostics_output_schema() -> SchemaIr {
+    let text = || SchemaIr::String {
+        min_len: Some(0),
+        max_len: None,
+        regex: None,
+        format: None,
+    };
+    let texts = || SchemaIr::Array {
+        items: Box::new(text()),
+        min_items: None,
+        max_items: None,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("component".to_string(), text()),
+            ("version".to_string(), text()),
+            (
+                "build".to_string(),
+                SchemaIr::Object {
+                    properties: BTreeMap::new(),
+                    required: Vec::new(),
+                    additional: AdditionalProperties::Allow,
+                },
+            ),
+            ("features".to_string(), texts()),
+            (
+                "engines".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Object {
+                        properties: BTreeMap::from([
+                            ("name".to_string(), text()),
+                            ("helpers".to_string(), texts()),
+                        ]),
+                        required: vec!["name".to_string(), "helpers".to_string()],
+                        additional: AdditionalProperties::Allow,
+                    }),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+            ("operations".to_string(), texts()),
+            ("wire_encodings".to_string(), texts()),
+            (
+                "locale_count".to_string(),
+                SchemaIr::Int {
+                    min: Some(0),
+                    max: None,
+                },
+            ),
+            ("i18n_bundle_hash".to_string(), text()),
+            (
+                "limits".to_string(),
+                SchemaIr::Object {
+                    properties: BTreeMap::new(),
+                    required: Vec::new(),
+                    additional: AdditionalProperties::Allow,
+                },
+            ),
+            (
+                "template_limits".to_string(),
+                SchemaIr::Object {
+                    properties: BTreeMap::new(),
+                    required: Vec::new(),
+                    additional: AdditionalProperties::Allow,
+                },
+            ),
+        ]),
+        required: vec![
+            "component".to_string(),
+            "version".to_string(),
+            "operations".to_string(),
+            "i18n_bundle_hash".to_string(),
+        ],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn verify_schema_hash_input_schema() -> SchemaIr {
+    let text = |min_len| SchemaIr::String {
+        min_len: Some(min_len),
+        max_len: None,
+        regex: None,
+        format: None,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            selector(VERIFY_SCHEMA_HASH_OPERATION),
+            ("expected".to_string(), text(1)),
+            ("target".to_string(), text(1)),
+        ]),
+        required: vec![OPERATION_SELECTOR.to_string(), "expected".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn verify_schema_hash_output_schema() -> SchemaIr {
+    let text = || SchemaIr::String {
+        min_len: Some(0),
+        max_len: None,
+        regex: None,
+        format: None,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("matches".to_string(), SchemaIr::Bool),
+            ("operation".to_string(), text()),
+            (
+                "actual".to_string(),
+                SchemaIr::Object {
+                    properties: BTreeMap::new(),
+                    required: Vec::new(),
+                    additional: AdditionalProperties::Schema(Box::new(text())),
+                },
+            ),
+        ]),
+        required: vec!["matches".to_string(), "actual".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn ast_input_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            selector(AST_OPERATION),
+            (
+                "text".to_string(),
+                SchemaIr::String {
+                    min_len: Some(0),
+                    max_len: None,
+                    regex: None,
+                    format: None,
+                },
+            ),
+        ]),
+        required: vec![OPERATION_SELECTOR.to_string(), "text".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+// Nodes are `{ kind, ... }` objects (text, comment, variable, helper,
+// partial, block with children and optional params/inverted, partial_block,
+// decorator, raw_block, else with an optional chained helper); the sets
+// list referenced names.
+fn ast_output_schema() -> SchemaIr {
+    let names = || SchemaIr::Array {
+        items: Box::new(SchemaIr::String {
+            min_len: Some(0),
+            max_len: None,
+            regex: None,
+            format: None,
+        }),
+        min_items: None,
+        max_items: None,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "nodes".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(SchemaIr::Object {
+                        properties: BTreeMap::new(),
+                        required: vec!["kind".to_string()],
+                        additional: AdditionalProperties::Allow,
+                    }),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+            ("variables".to_string(), names()),
+            ("helpers".to_string(), names()),
+            ("partials".to_string(), names()),
+        ]),
+        required: vec![
+            "nodes".to_string(),
+            "variables".to_string(),
+            "helpers".to_string(),
+            "partials".to_string(),
+        ],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+fn schemas_input_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([selector(SCHEMAS_OPERATION)]),
+        required: vec![OPERATION_SELECTOR.to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+// `current_config` is the node's stored config; only update mode reads it.
+fn qa_spec_input_schema() -> SchemaIr {
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            selector(QA_SPEC_OPERATION),
+            (
+                "mode".to_string(),
+                SchemaIr::Enum {
+                    values: crate::QA_MODES
+                        .iter()
+                        .map(|mode| CborValue::Text((*mode).to_string()))
+                        .collect(),
+                },
+            ),
+            (
+                "current_config".to_string(),
+                SchemaIr::Object {
+                    properties: BTreeMap::new(),
+                    required: Vec::new(),
+                    additional: AdditionalProperties::Allow,
+                },
+            ),
+        ]),
+        required: vec![OPERATION_SELECTOR.to_string(), "mode".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+// A `ComponentQaSpec`, as the WIT `qa-spec` export returns it.
+fn qa_spec_output_schema() -> SchemaIr {
+    let any = || SchemaIr::Object {
+        properties: BTreeMap::new(),
+        required: Vec::new(),
+        additional: AdditionalProperties::Allow,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            (
+                "mode".to_string(),
+                SchemaIr::String {
+                    min_len: Some(1),
+                    max_len: None,
+                    regex: None,
+                    format: None,
+                },
+            ),
+            (
+                "questions".to_string(),
+                SchemaIr::Array {
+                    items: Box::new(any()),
+                    min_items: None,
+                    max_items: None,
+                },
+            ),
+            ("defaults".to_string(), any()),
+        ]),
+        required: vec!["mode".to_string(), "questions".to_string()],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+// Each entry is a draft 2020-12 JSON Schema document.
+fn schemas_output_schema() -> SchemaIr {
+    let document = || SchemaIr::Object {
+        properties: BTreeMap::new(),
+        required: Vec::new(),
+        additional: AdditionalProperties::Allow,
+    };
+    SchemaIr::Object {
+        properties: BTreeMap::from([
+            ("input".to_string(), document()),
+            ("output".to_string(), document()),
+            ("config".to_string(), document()),
+        ]),
+        required: vec![
+            "input".to_string(),
+            "output".to_string(),
+            "config".to_string(),
+        ],
+        additional: AdditionalProperties::Allow,
+    }
+}
+
+// Host-side ceilings on the template text; `templates.limits` may only
+// tighten them.
+fn template_constraints() -> BTreeMap<String, CborValue> {
+    let limit = |value: usize| CborValue::Integer((value as u64).into());
+    BTreeMap::from([
+        (
+            "max_template_bytes".to_string(),
+            limit(crate::limits::MAX_TEMPLATE_BYTES),
+        ),
+        (
+            "max_block_depth".to_string(),
+            limit(crate::limits::MAX_BLOCK_DEPTH),
+        ),
+        (
+            "max_helper_calls".to_string(),
+            limit(crate::limits::MAX_HELPER_CALLS),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_ids_are_unique_and_default_is_registered() {
+        let mut ids: Vec<_> = OPERATIONS.iter().map(|operation| operation.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), OPERATIONS.len());
+        assert_eq!(default_operation().id, DEFAULT_OPERATION);
+        assert!(find("missing").is_none());
+    }
+
+    #[test]
+    fn component_operation_hash_matches_its_own_schemas() {
+        let config = SchemaIr::Object {
+            properties: BTreeMap::new(),
+            required: Vec::new(),
+            additional: AdditionalProperties::Allow,
+        };
+        for spec in OPERATIONS {
+            let operation = spec.to_component_operation(&config).expect("operation");
+            assert_eq!(operation.id, spec.id);
+            assert_eq!(Ok(operation.schema_hash.clone()), spec.schema_hash(&config));
+            assert_eq!(operation.schema_hash.len(), 64);
+        }
+    }
+
+    #[test]
+    fn handle_message_publishes_template_limits_as_constraints() {
+        let constraints = (default_operation().constraints)();
+        assert_eq!(
+            constraints["max_template_bytes"],
+            CborValue::Integer((crate::limits::MAX_TEMPLATE_BYTES as u64).into())
+        );
+        assert!(constraints.contains_key("max_block_depth"));
+        assert!(constraints.contains_key("max_helper_calls"));
+        assert!((find(DIAGNOSTICS_OPERATION).unwrap().constraints)().is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+
+use crate::ast::TemplateAst;
+use crate::limits::MAX_TEMPLATE_BYTES;
+
+// Validation attached to a QA question. `Question` has no rule slots, so
+// setup UIs read the rules from describe metadata (`qa_validation`) and the
+// question's `error` names the catalog message shown when a rule fails.
+// `apply_answers` enforces the same table through `check`. Lengths are
+// UTF-8 bytes, published per rule as `length_unit`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct QuestionRule {
+    pub question_id: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_len: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_len: Option<usize>,
+    pub length_unit: &'static str,
+    // Published for UIs; `check` is the authoritative (regex-free) matcher.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<&'static str>,
+    pub error_key: &'static str,
+    #[serde(skip)]
+    pub check: fn(&str) -> bool,
+}
+
+impl QuestionRule {
+    pub fn validate(&self, answer: &str) -> bool {
+        let len = answer.len();
+        self.min_len.is_none_or(|min| len >= min)
+            && self.max_len.is_none_or(|max| len <= max)
+            && (self.check)(answer)
+    }
+}
+
+// Matches the byte limits in `limits`, so both checks agree on a length.
+pub const LENGTH_UNIT: &str = "bytes";
+
+pub const QUESTION_RULES: &[QuestionRule] = &[
+    QuestionRule {
+        question_id: "templates.text",
+        min_len: None,
+        max_len: Some(MAX_TEMPLATE_BYTES),
+        length_unit: LENGTH_UNIT,
+        pattern: None,
+        error_key: "qa.text.error",
+        check: is_well_formed_template,
+    },
+    QuestionRule {
+        question_id: "rollback",
+        min_len: Some(2),
+        max_len: None,
+        length_unit: LENGTH_UNIT,
+        pattern: Some("^v[1-9][0-9]*$"),
+        error_key: "qa.rollback.error",
+        check: is_version_id,
+    },
+];
+
+pub fn rule_for(question_id: &str) -> Option<&'static QuestionRule> {
+    QUESTION_RULES
+        .iter()
+        .find(|rule| rule.question_id == question_id)
+}
+
+fn is_well_formed_template(text: &str) -> bool {
+    TemplateAst::parse(text).is_ok()
+}
+
+// `^v[1-9][0-9]*$`, matching the ids `record_template_version` hands out.
+fn is_version_id(id: &str) -> bool {
+    id.strip_prefix('v').is_some_and(|digits| {
+        !digits.starts_with('0') && !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_accept_valid_and_reject_invalid_answers() {
+        let text = rule_for("templates.text").expect("text rule");
+        assert!(text.validate("Hello {{name}}"));
+        assert!(!text.validate("{{#if a}}"));
+        assert!(!text.validate(&"x".repeat(MAX_TEMPLATE_BYTES + 1)));
+        // Byte lengths: a multi-byte text that fits in chars still counts bytes.
+        assert!(!text.validate(&"é".repeat(MAX_TEMPLATE_BYTES / 2 + 1)));
+        for valid in [
+            "{{#> layout title=page.title}}fallback{{/layout}}",
+            r#"{{#*inline "row"}}<td>{{cell}}</td>{{/inline}}{{> row}}"#,
+            "{{{{raw}}}} {{#if}} stays literal {{{{/raw}}}}",
+        ] {
+            assert!(text.validate(valid), "{valid}");
+        }
+
+        let rollback = rule_for("rollback").expect("rollback rule");
+        for ok in ["v1", "v20"] {
+            assert!(rollback.validate(ok), "{ok}");
+        }
+        for bad in ["", "v", "v0", "v01", "1", "v1a", "V1"] {
+            assert!(!rollback.validate(bad), "{bad}");
+        }
+        assert!(rule_for("missing").is_none());
+
+        let published = serde_json::to_value(QUESTION_RULES).unwrap();
+        assert_eq!(published[0]["length_unit"], "bytes");
+    }
+}
@@ -1,4 +1,7 @@
-use component_templates::{describe_payload, handle_message};
+use component_templates::codec::{self, WireEncoding};
+use component_templates::{
+    describe_payload, example_invocation, handle_message, run_bytes, run_output,
+};
 
 #[test]
 fn describe_mentions_world() {
@@ -15,3 +18,58 @@ fn handle_echoes_input() {
     let response = handle_message("invoke", "ping");
     assert!(response.contains("ping"));
 }
+
+#[test]
+fn example_invocation_renders_through_run_output() {
+    let example = example_invocation();
+    let output = run_output(&example["input"]);
+    assert_eq!(output, example["output"]);
+    assert!(output["message"].as_str().unwrap().contains("Hello"));
+}
+
+#[test]
+fn run_bytes_answers_in_the_caller_encoding() {
+    let input = serde_json::json!({ "input": "ping" });
+    let expected = run_output(&input);
+
+    let json_out = run_bytes(&serde_json::to_vec(&input).unwrap());
+    assert_eq!(WireEncoding::detect(&json_out), WireEncoding::Json);
+    let decoded: serde_json::Value = serde_json::from_slice(&json_out).unwrap();
+    assert_eq!(decoded, expected);
+
+    for encoding in [WireEncoding::Cbor, WireEncoding::MessagePack] {
+        let encoded = codec::encode(encoding, &input).unwrap();
+        let output = run_bytes(&encoded);
+        assert_eq!(codec::decode(&output), (encoding, expected.clone()));
+    }
+}
+
+#[test]
+fn run_bytes_answers_cbor_when_the_header_looks_like_msgpack() {
+    // A CBOR two-item array starts with 0x82, a msgpack fixmap header.
+    let encoded = codec::encode(WireEncoding::Cbor, &serde_json::json!(["a", "b"])).unwrap();
+    assert_eq!(WireEncoding::detect(&encoded), WireEncoding::MessagePack);
+
+    let output = run_bytes(&encoded);
+    assert_eq!(WireEncoding::detect(&output), WireEncoding::Cbor);
+    assert_eq!(codec::decode(&output).0, WireEncoding::Cbor);
+}
+
+#[test]
+fn run_dispatches_on_operation_field() {
+    let diagnostics = run_output(&serde_json::json!({ "$operation": "diagnostics" }));
+    assert_eq!(diagnostics["component"], "ai.greentic.component-templates");
+
+    // A plain `operation` field is message data rather than a selector.
+    assert_eq!(
+        run_output(&serde_json::json!({ "operation": "diagnostics", "input": "x" })),
+        run_output(&serde_json::json!({ "input": "x" }))
+    );
+
+    // A misspelled or non-string selector is rejected, not run as a message.
+    for selector in [serde_json::json!("diagnostic"), serde_json::json!(1)] {
+        let output = run_output(&serde_json::json!({ "$operation": selector, "input": "x" }));
+        assert_eq!(output["error"]["code"], "invalid_input");
+        assert!(output.get("message").is_none());
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use component_templates::contract_bytes;
+use greentic_types::cbor::canonical;
+
+// Golden copies of the exported contract live in tests/snapshots/contract.
+// Each file records the blake3-128 of the canonical CBOR plus its decoded
+// JSON form, so a drift shows up as a readable diff in review.
+// Regenerate with: UPDATE_SNAPSHOTS=1 cargo test --test contract_snapshots
+fn snapshot_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/contract")
+}
+
+fn snapshot(bytes: &[u8]) -> serde_json::Value {
+    let hash: String = canonical::blake3_128(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    let value: serde_json::Value = canonical::from_cbor(bytes).expect("contract decodes");
+    serde_json::json!({ "blake3_128": hash, "value": value })
+}
+
+#[test]
+fn contract_matches_snapshots() {
+    let artifacts = contract_bytes().expect("encode contract");
+    let dir = snapshot_dir();
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+
+    for (name, bytes) in &artifacts {
+        let path = dir.join(format!("{name}.json"));
+        let actual = snapshot(bytes);
+        if update {
+            fs::create_dir_all(&dir).expect("create snapshot dir");
+            let mut text = serde_json::to_string_pretty(&actual).expect("snapshot json");
+            text.push('\n');
+            fs::write(&path, text).expect("write snapshot");
+            continue;
+        }
+        let expected: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {err}", path.display())),
+        )
+        .expect("snapshot json");
+        assert_eq!(
+            actual, expected,
+            "{name} drifted; rerun with UPDATE_SNAPSHOTS=1 and review the diff"
+        );
+    }
+}
+
+#[test]
+fn every_snapshot_has_a_contract_artifact() {
+    let artifacts = contract_bytes().expect("encode contract");
+    for entry in fs::read_dir(snapshot_dir()).expect("snapshot dir") {
+        let path = entry.expect("snapshot entry").path();
+        let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap();
+        assert!(artifacts.contains_key(name), "stale snapshot {name}");
+    }
+}
+
+#[test]
+fn contract_covers_describe_schemas_and_qa_modes() {
+    let names: Vec<_> = contract_bytes()
+        .expect("encode contract")
+        .into_keys()
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "describe",
+            "qa.default",
+            "qa.remove",
+            "qa.setup",
+            "qa.update",
+            "schema.config",
+            "schema.input",
+            "schema.output",
+        ]
+    );
+}
@@ -0,0 +1,42 @@
+use component_templates::codec::WireEncoding;
+use component_templates::fixtures::{
+    Fixture, fixture_names, fixture_path, load_fixture, run_fixture,
+};
+
+// Regenerate after an intentional behaviour change with:
+// UPDATE_SNAPSHOTS=1 cargo test --test fixtures
+#[test]
+fn every_fixture_replays_byte_for_byte() {
+    let names = fixture_names().expect("list fixtures");
+    assert!(!names.is_empty());
+
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    for name in names {
+        if update {
+            let fixture = load_fixture(&name).expect("load fixture");
+            let recorded = Fixture::record(fixture.encoding, fixture.input).expect("record");
+            let mut text = serde_json::to_string_pretty(&recorded).expect("fixture json");
+            text.push('\n');
+            std::fs::write(fixture_path(&name), text).expect("write fixture");
+            continue;
+        }
+        run_fixture(&name).unwrap_or_else(|err| panic!("{err}"));
+    }
+}
+
+#[test]
+fn fixtures_cover_every_wire_encoding() {
+    let encodings: Vec<_> = fixture_names()
+        .expect("list fixtures")
+        .iter()
+        .map(|name| load_fixture(name).expect("load fixture").encoding)
+        .collect();
+    for encoding in WireEncoding::ALL {
+        assert!(encodings.contains(&encoding), "{encoding:?}");
+    }
+}
+
+#[test]
+fn unknown_fixture_is_an_error() {
+    assert!(run_fixture("does-not-exist").is_err());
+}
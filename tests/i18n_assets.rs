@@ -1,5 +1,5 @@
+use component_templates::i18n_bundle::flatten_catalog;
 use serde_json::Value;
-use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -17,7 +17,8 @@ fn locales_and_en_assets_are_well_formed() {
     assert!(locales.iter().any(|l| l == "nl-NL"), "nl-NL locale missing");
 
     let en_raw = fs::read_to_string(&en_path).expect("read en.json");
-    let en_map: BTreeMap<String, String> = serde_json::from_str(&en_raw).expect("parse en.json");
+    let en_json: Value = serde_json::from_str(&en_raw).expect("parse en.json");
+    let en_map = flatten_catalog(&en_json).expect("flatten en.json");
     assert!(!en_map.is_empty(), "en.json must contain at least one key");
     for (key, value) in &en_map {
         assert!(!key.trim().is_empty(), "en.json contains empty key");
@@ -40,3 +41,75 @@ fn locales_and_en_assets_are_well_formed() {
         );
     }
 }
+
+#[test]
+fn every_locale_keeps_en_placeholders() {
+    let coverage = component_templates::i18n::coverage();
+    assert!(!coverage.is_empty(), "embedded bundle has no catalogs");
+
+    for entry in coverage {
+        assert!(
+            entry.placeholder_mismatches.is_empty(),
+            "{} has placeholder drift: {:?}",
+            entry.locale,
+            entry.placeholder_mismatches
+        );
+    }
+}
+
+#[test]
+fn incremental_subset_flattens_nested_catalogs() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = std::env::temp_dir().join(format!("i18n-subset-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("create temp catalog dir");
+    fs::write(dir.join("locales.json"), r#"["fr"]"#).unwrap();
+    fs::write(
+        dir.join("en.json"),
+        r#"{ "qa": { "text": { "label": "Text", "help": "Help" } }, "title": "Title" }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("fr.json"),
+        r#"{ "qa": { "text": { "label": "Texte" } }, "title": "Titre" }"#,
+    )
+    .unwrap();
+    // Every en key already translated once, so only gaps are dirty.
+    let state = dir.join("state.json");
+    let hash = |value: &str| {
+        let output = std::process::Command::new("python3")
+            .args([
+                "-c",
+                "import hashlib,sys; print(hashlib.sha256(sys.argv[1].encode()).hexdigest())",
+                value,
+            ])
+            .output()
+            .expect("python3");
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    };
+    fs::write(
+        &state,
+        serde_json::json!({ "en_hashes": {
+            "qa.text.label": hash("Text"),
+            "qa.text.help": hash("Help"),
+            "title": hash("Title"),
+        } })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = std::process::Command::new("bash")
+        .arg(root.join("tools/i18n.sh"))
+        .arg("subset")
+        .env("I18N_DIR", &dir)
+        .env("I18N_STATE_FILE", &state)
+        .output()
+        .expect("run tools/i18n.sh subset");
+    let _ = fs::remove_dir_all(&dir);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let subset: Value = serde_json::from_slice(&output.stdout).expect("subset json");
+    assert_eq!(subset, serde_json::json!({ "qa.text.help": "Help" }));
+}
@@ -0,0 +1,71 @@
+use component_templates::i18n::{self, DEFAULT_FALLBACKS};
+use proptest::prelude::*;
+
+// Well-formed-ish tags: language, optional script, optional region, in any
+// case and with either separator.
+fn locale_tag() -> impl Strategy<Value = String> {
+    (
+        "[a-zA-Z]{2,3}",
+        proptest::option::of("[a-zA-Z]{4}"),
+        proptest::option::of("[a-zA-Z]{2}|[0-9]{3}"),
+        prop_oneof![Just("-"), Just("_")],
+    )
+        .prop_map(|(language, script, region, sep)| {
+            let mut tag = language;
+            for part in script.into_iter().chain(region) {
+                tag.push_str(sep);
+                tag.push_str(&part);
+            }
+            tag
+        })
+}
+
+// Anything a host might put in a locale header, including junk.
+fn any_locale() -> impl Strategy<Value = String> {
+    prop_oneof![locale_tag(), "[a-zA-Z0-9_ \\-]{0,16}", any::<String>()]
+}
+
+fn fallback_table() -> impl Strategy<Value = Vec<(String, String)>> {
+    proptest::collection::vec((locale_tag(), locale_tag()), 0..8)
+}
+
+proptest! {
+    #[test]
+    fn normalize_is_idempotent(locale in any_locale()) {
+        let once = i18n::normalize(&locale);
+        prop_assert_eq!(i18n::normalize(&once), once);
+    }
+
+    #[test]
+    fn normalize_ignores_separator_and_case(tag in locale_tag()) {
+        let expected = i18n::normalize(&tag);
+        prop_assert_eq!(i18n::normalize(&tag.replace('-', "_")), expected.clone());
+        prop_assert_eq!(i18n::normalize(&tag.to_ascii_uppercase()), expected.clone());
+        prop_assert_eq!(i18n::normalize(&tag.to_ascii_lowercase()), expected);
+    }
+
+    #[test]
+    fn fallback_chain_terminates_in_en_without_repeats(
+        locale in any_locale(),
+        table in fallback_table(),
+    ) {
+        let table: Vec<(&str, &str)> = table
+            .iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+            .collect();
+        for fallbacks in [table.as_slice(), DEFAULT_FALLBACKS] {
+            let chain = i18n::locale_chain_with(&locale, fallbacks);
+            prop_assert!(chain.iter().any(|tag| tag == "en"));
+            let mut unique = chain.clone();
+            unique.sort();
+            unique.dedup();
+            prop_assert_eq!(unique.len(), chain.len());
+        }
+    }
+
+    #[test]
+    fn resolved_locale_always_has_a_catalog(locale in any_locale()) {
+        let resolved = i18n::resolve_supported(&locale);
+        prop_assert!(i18n::i18n_report(&resolved).catalog_present);
+    }
+}
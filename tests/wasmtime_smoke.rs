@@ -0,0 +1,174 @@
+// Drives the compiled wasm32-wasip2 artifact through wasmtime, so WIT binding
+// regressions surface before publishing. Opt-in:
+//   cargo test --features wasmtime-smoke --test wasmtime_smoke
+// Set COMPONENT_TEMPLATES_WASM to test a prebuilt artifact instead.
+#![cfg(feature = "wasmtime-smoke")]
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use component_templates::codec::{self, WireEncoding};
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::ComponentDescribe;
+use wasmtime::component::{Component, Linker, ResourceTable};
+use wasmtime::{Engine, Store};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
+
+wasmtime::component::bindgen!({
+    path: "wit",
+    world: "component-v0-v6-v0",
+});
+
+use exports::greentic::component::component_qa::QaMode;
+
+struct Host {
+    wasi: WasiCtx,
+    table: ResourceTable,
+}
+
+impl WasiView for Host {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView {
+            ctx: &mut self.wasi,
+            table: &mut self.table,
+        }
+    }
+}
+
+// Built once per test binary, in its own target dir so the nested cargo
+// never waits on the lock held by the outer `cargo test`.
+fn artifact() -> &'static PathBuf {
+    static ARTIFACT: OnceLock<PathBuf> = OnceLock::new();
+    ARTIFACT.get_or_init(|| {
+        if let Some(path) = std::env::var_os("COMPONENT_TEMPLATES_WASM") {
+            return PathBuf::from(path);
+        }
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let target_dir = root.join("target/wasmtime-smoke");
+        let status = Command::new(env!("CARGO"))
+            .args(["build", "--release", "--target", "wasm32-wasip2"])
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .current_dir(&root)
+            .status()
+            .expect("spawn cargo build");
+        assert!(status.success(), "wasm32-wasip2 build failed");
+        target_dir.join("wasm32-wasip2/release/component_templates.wasm")
+    })
+}
+
+fn instantiate() -> (Store<Host>, ComponentV0V6V0) {
+    let engine = Engine::default();
+    let component = Component::from_file(&engine, artifact()).expect("load component");
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::p2::add_to_linker_sync(&mut linker).expect("link wasi");
+    let mut store = Store::new(
+        &engine,
+        Host {
+            wasi: WasiCtxBuilder::new().build(),
+            table: ResourceTable::new(),
+        },
+    );
+    let bindings =
+        ComponentV0V6V0::instantiate(&mut store, &component, &linker).expect("instantiate");
+    (store, bindings)
+}
+
+fn decode(bytes: &[u8]) -> serde_json::Value {
+    let (encoding, value) = codec::decode(bytes);
+    assert_eq!(encoding, WireEncoding::Cbor);
+    value
+}
+
+#[test]
+fn describe_matches_native_contract() {
+    let (mut store, bindings) = instantiate();
+    let describe = bindings
+        .greentic_component_component_descriptor()
+        .call_describe(&mut store)
+        .expect("describe");
+    let contract = component_templates::contract_bytes().expect("contract");
+
+    // Build provenance is stripped from the contract; compare the rest.
+    let mut describe: ComponentDescribe = canonical::from_cbor(&describe).expect("decode describe");
+    assert!(describe.metadata.remove("build").is_some());
+    assert_eq!(
+        codec::encode(WireEncoding::Cbor, &describe).unwrap(),
+        contract["describe"]
+    );
+    let info = decode(
+        &bindings
+            .greentic_component_component_descriptor()
+            .call_get_component_info(&mut store)
+            .expect("get-component-info"),
+    );
+    assert_eq!(info["id"], "ai.greentic.component-templates");
+}
+
+#[test]
+fn schemas_match_native_contract() {
+    let (mut store, bindings) = instantiate();
+    let schema = bindings.greentic_component_component_schema();
+    let contract = component_templates::contract_bytes().expect("contract");
+
+    assert_eq!(
+        schema.call_input_schema(&mut store).unwrap(),
+        contract["schema.input"]
+    );
+    assert_eq!(
+        schema.call_output_schema(&mut store).unwrap(),
+        contract["schema.output"]
+    );
+    assert_eq!(
+        schema.call_config_schema(&mut store).unwrap(),
+        contract["schema.config"]
+    );
+}
+
+#[test]
+fn run_echoes_input_and_keeps_state() {
+    let (mut store, bindings) = instantiate();
+    let input = codec::encode(WireEncoding::Cbor, &serde_json::json!({ "input": "ping" })).unwrap();
+    let state = codec::encode(WireEncoding::Cbor, &serde_json::json!({ "n": 1 })).unwrap();
+
+    let result = bindings
+        .greentic_component_component_runtime()
+        .call_run(&mut store, &input, &state)
+        .expect("run");
+
+    assert_eq!(
+        decode(&result.output),
+        component_templates::run_output(&serde_json::json!({ "input": "ping" }))
+    );
+    assert_eq!(result.new_state, state);
+}
+
+#[test]
+fn qa_spec_and_apply_answers_round_trip() {
+    let (mut store, bindings) = instantiate();
+    let qa = bindings.greentic_component_component_qa();
+    let contract = component_templates::contract_bytes().expect("contract");
+
+    for (mode, key) in [
+        (QaMode::Default, "qa.default"),
+        (QaMode::Setup, "qa.setup"),
+        (QaMode::Update, "qa.update"),
+        (QaMode::Remove, "qa.remove"),
+    ] {
+        assert_eq!(qa.call_qa_spec(&mut store, mode).unwrap(), contract[key]);
+    }
+
+    let current = codec::encode(WireEncoding::Cbor, &serde_json::json!({})).unwrap();
+    let answers = codec::encode(WireEncoding::Cbor, &serde_json::json!({ "text": "Hi" })).unwrap();
+    let updated = qa
+        .call_apply_answers(&mut store, QaMode::Setup, &current, &answers)
+        .expect("apply-answers");
+    assert_eq!(decode(&updated)["templates"]["text"], "Hi");
+
+    let keys = bindings
+        .greentic_component_component_i18n()
+        .call_i18n_keys(&mut store)
+        .expect("i18n-keys");
+    assert_eq!(keys, component_templates::i18n::all_keys());
+}
@@ -4,10 +4,10 @@ set -euo pipefail
 ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
 cd "$ROOT_DIR"
 
-I18N_DIR="$ROOT_DIR/assets/i18n"
+I18N_DIR="${I18N_DIR:-$ROOT_DIR/assets/i18n}"
 LOCALES_FILE="$I18N_DIR/locales.json"
 EN_FILE="$I18N_DIR/en.json"
-STATE_FILE="$ROOT_DIR/.i18n/translate-index.json"
+STATE_FILE="${I18N_STATE_FILE:-$ROOT_DIR/.i18n/translate-index.json}"
 MODE="${1:-all}"
 LOCALE="${LOCALE:-en}"
 AUTH_MODE="${AUTH_MODE:-auto}"
@@ -25,13 +25,17 @@ info() {
 
 usage() {
   cat <<'USAGE'
-Usage: tools/i18n.sh [all|translate|validate|status|check-auth]
+Usage: tools/i18n.sh [all|translate|validate|status|subset|check-auth]
+
+  subset              Print the keys an incremental translate would send (no translator needed)
 
 Environment overrides:
   LOCALE=...          Locale for translator runtime messages (default: en)
   AUTH_MODE=...       Auth mode for translate (default: auto)
   TRANSLATOR_BIN=...  Translator command (default: greentic-i18n-translator)
   I18N_INCREMENTAL=1  Translate only missing/stale keys (default: 1; set 0 for full en.json)
+  I18N_DIR=...        Catalog directory (default: assets/i18n)
+  I18N_STATE_FILE=... Incremental translation index (default: .i18n/translate-index.json)
 USAGE
 }
 
@@ -139,10 +143,21 @@ i18n_dir = Path(sys.argv[1])
 en_path = Path(sys.argv[2])
 locales_path = Path(sys.argv[3])
 
+def flatten(value, prefix=""):
+    flat = {}
+    for key, item in value.items():
+        full_key = f"{prefix}.{key}" if prefix else key
+        if isinstance(item, dict):
+            flat.update(flatten(item, full_key))
+        else:
+            flat[full_key] = item
+    return flat
+
 en = json.loads(en_path.read_text(encoding="utf-8"))
 if not isinstance(en, dict):
     print(f"error: {en_path} must be a JSON object", file=sys.stderr)
     sys.exit(1)
+en = flatten(en)
 
 locales = json.loads(locales_path.read_text(encoding="utf-8"))
 if not isinstance(locales, list):
@@ -161,6 +176,7 @@ for locale in locales:
         print(f"error: {locale_path} must be a JSON object", file=sys.stderr)
         failed = True
         continue
+    raw = flatten(raw)
 
     missing = [k for k in en.keys() if k not in raw]
     empty = [k for k in en.keys() if k in raw and str(raw[k]).strip() == ""]
@@ -195,10 +211,22 @@ state_path = Path(sys.argv[4])
 subset_path = Path(sys.argv[5])
 next_state_path = Path(sys.argv[6])
 
+# Same dotted-key view as check_key_coverage and the build-time bundle.
+def flatten(value, prefix=""):
+    flat = {}
+    for key, item in value.items():
+        full_key = f"{prefix}.{key}" if prefix else key
+        if isinstance(item, dict):
+            flat.update(flatten(item, full_key))
+        else:
+            flat[full_key] = item
+    return flat
+
 en = json.loads(en_path.read_text(encoding="utf-8"))
 if not isinstance(en, dict):
     print("error: en.json must be a JSON object", file=sys.stderr)
     sys.exit(1)
+en = flatten(en)
 
 locales = json.loads(locales_path.read_text(encoding="utf-8"))
 if not isinstance(locales, list):
@@ -244,6 +272,7 @@ for locale in locales:
     if not isinstance(raw, dict):
         dirty.update(en.keys())
         continue
+    raw = flatten(raw)
     for key in en.keys():
         if key not in raw:
             dirty.add(key)
@@ -335,6 +364,15 @@ case "$MODE" in
     run_status
     check_key_coverage
     ;;
+  subset)
+    ensure_locale_files
+    json_syntax_check
+    subset_file="$(mktemp)"
+    next_state_file="$(mktemp)"
+    prepare_incremental_subset "$subset_file" "$next_state_file" >/dev/null
+    cat "$subset_file"
+    rm -f "$subset_file" "$next_state_file"
+    ;;
   check-auth)
     ensure_codex_installed
     ensure_codex_login